@@ -0,0 +1,137 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Character classes defined by RFCs.
+//!
+//! Each class is available as a `ByteSet` lookup table, which plugs into `bs_collect_set!()` and
+//! `bs_skip_set!()`, and as a predicate, which plugs into `bs_collect_when!()` and
+//! `bs_collect_until!()`.
+
+use set::ByteSet;
+
+/// RFC 5322 `atext`: `A-Z`, `a-z`, `0-9` and ``!#$%&'*+-/=?^_`{|}~``.
+pub const ATEXT: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"!#$%&'*+-/=?^_`{|}~");
+
+/// RFC 4648 base64 alphabet: `A-Z`, `a-z`, `0-9`, `+` and `/`. The `=` padding is not included.
+pub const BASE64: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"+/");
+
+/// RFC 4648 URL and filename safe base64 alphabet: `A-Z`, `a-z`, `0-9`, `-` and `_`. The `=`
+/// padding is not included.
+pub const BASE64_URL: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"-_");
+
+/// RFC 7230 `field-vchar`: `VCHAR` and `obs-text`.
+pub const FIELD_VCHAR: ByteSet = VCHAR.union(OBS_TEXT);
+
+/// RFC 3986 `gen-delims`: `:/?#[]@`.
+pub const GEN_DELIMS: ByteSet = ByteSet::bytes(b":/?#[]@");
+
+/// RFC 7230 `obs-text`: `0x80-0xFF`.
+pub const OBS_TEXT: ByteSet = ByteSet::range(0x80, 0xFF);
+
+/// RFC 3986 `pchar`: `unreserved`, `sub-delims`, `:`, `@` and the `%` that starts `pct-encoded`.
+///
+/// The two hex digits that follow `%` are not checked.
+pub const PCHAR: ByteSet = UNRESERVED.union(SUB_DELIMS).with_bytes(b":@%");
+
+/// RFC 3986 `reserved`: `gen-delims` and `sub-delims`.
+pub const RESERVED: ByteSet = GEN_DELIMS.union(SUB_DELIMS);
+
+/// RFC 3986 `sub-delims`: `!$&'()*+,;=`.
+pub const SUB_DELIMS: ByteSet = ByteSet::bytes(b"!$&'()*+,;=");
+
+/// RFC 7230 `tchar`: `A-Z`, `a-z`, `0-9` and ``!#$%&'*+-.^_`|~``.
+pub const TCHAR: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"!#$%&'*+-.^_`|~");
+
+/// RFC 3986 `unreserved`: `A-Z`, `a-z`, `0-9` and `-._~`.
+pub const UNRESERVED: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"-._~");
+
+/// RFC 5234 `VCHAR`: `0x21-0x7E`.
+pub const VCHAR: ByteSet = ByteSet::VISIBLE_7BIT;
+
+/// Indicates that a byte is in the RFC 5322 `atext` class.
+#[inline]
+pub fn is_atext(byte: u8) -> bool {
+    ATEXT.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 4648 base64 alphabet.
+#[inline]
+pub fn is_base64(byte: u8) -> bool {
+    BASE64.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 4648 URL and filename safe base64 alphabet.
+#[inline]
+pub fn is_base64_url(byte: u8) -> bool {
+    BASE64_URL.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 7230 `field-vchar` class.
+#[inline]
+pub fn is_field_vchar(byte: u8) -> bool {
+    FIELD_VCHAR.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 3986 `gen-delims` class.
+#[inline]
+pub fn is_gen_delim(byte: u8) -> bool {
+    GEN_DELIMS.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 7230 `obs-text` class.
+#[inline]
+pub fn is_obs_text(byte: u8) -> bool {
+    byte > 0x7F
+}
+
+/// Indicates that a byte is in the RFC 3986 `pchar` class, counting `%` as the start of
+/// `pct-encoded`.
+#[inline]
+pub fn is_pchar(byte: u8) -> bool {
+    PCHAR.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 3986 `reserved` class.
+#[inline]
+pub fn is_reserved(byte: u8) -> bool {
+    RESERVED.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 3986 `sub-delims` class.
+#[inline]
+pub fn is_sub_delim(byte: u8) -> bool {
+    SUB_DELIMS.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 7230 `tchar` class.
+#[inline]
+pub fn is_tchar(byte: u8) -> bool {
+    TCHAR.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 3986 `unreserved` class.
+#[inline]
+pub fn is_unreserved(byte: u8) -> bool {
+    UNRESERVED.contains(byte)
+}
+
+/// Indicates that a byte is in the RFC 5234 `VCHAR` class.
+#[inline]
+pub fn is_vchar(byte: u8) -> bool {
+    byte > 0x20 && byte < 0x7F
+}
@@ -16,23 +16,1077 @@
 // | Author: Sean Kerr <sean@metatomic.io>                                                         |
 // +-----------------------------------------------------------------------------------------------+
 
+//! Byte slice parsing.
+//!
+//! Every `bs_*` macro has an equivalent method provided by the `ByteContext` trait, which is
+//! implemented by `ByteStream` and the buffered streams. The trait must be in scope for the methods
+//! to resolve, and is easiest to import with the prelude:
+//!
+//! ```
+//! extern crate byte_slice;
+//!
+//! use byte_slice::prelude::*;
+//! use byte_slice::{ ByteStream, Collect };
+//!
+//! fn main() {
+//!     let mut stream = ByteStream::new(b"Content-Length: 42\r\n");
+//!
+//!     assert_eq!(stream.find(b':'), Some(14));
+//!
+//!     stream.jump(16);
+//!     stream.mark();
+//!
+//!     assert_eq!(stream.collect_digits::<u32>(), Collect::Done(42));
+//!     assert_eq!(stream.slice_ignore(), b"42");
+//! }
+//! ```
+
+#![allow(clippy::redundant_field_names)]
+
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
 #[macro_use]
 pub mod macros;
+pub mod classes;
+pub mod number;
+pub mod parser;
+pub mod prelude;
+pub mod search;
+pub mod set;
+pub mod stream;
 
 #[cfg(test)]
 mod test;
 
+use number::FloatScan;
 use std::fmt;
+use std::str::FromStr;
 
-/// Default byte stream type.
+pub use number::{ DigitPolicy, FloatFormat, NumberState, SignedAccumulator,
+                  UnsignedAccumulator };
+pub use parser::ParseResult;
+pub use search::{ Finder, FinderRev, Keywords, Match, MatchKind, MultiFinder };
+pub use set::ByteSet;
+pub use stream::{ ChunkedStream, ReadStream };
+
+#[cfg(feature = "tokio")]
+pub use stream::AsyncStream;
+
+/// Collection result.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Collect<T> {
+    /// Collection stopped upon locating a byte that is not part of the collection.
+    Done(T),
+
+    /// End-of-stream was located.
+    Eos(T),
+
+    /// An overflow would have occurred.
+    Overflow(T),
+
+    /// The digit policy would have been violated.
+    Violation(T)
+}
+
+/// Float collection result.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatCollect<T> {
+    /// Collection stopped upon locating a byte that is not part of the number.
+    Done(T),
+
+    /// End-of-stream was located. Contains the value when the collected bytes form a complete
+    /// number.
+    Eos(Option<T>),
+
+    /// A byte was located that cannot appear at its position within the number.
+    Invalid
+}
+
+/// Byte stream context.
 ///
-/// All stream macros will accept any struct given as `$context`, as long as they contain the
-/// following four fields:
+/// All stream macros will accept any type given as `$context`, as long as it implements this
+/// trait. The implementing type is free to store its state however it chooses, as long as it
+/// provides access to the following four pieces of state:
 ///
 /// - `byte` (u8) The most recent byte.
 /// - `mark_index` (usize) Starting index of a collection of marked bytes.
 /// - `stream` (&[u8]) Stream of bytes.
 /// - `stream_index` (usize) Current stream index.
+///
+/// Each stream macro has an equivalent provided method, so that the same functionality is
+/// available without the use of macros. The methods are provided by this trait rather than being
+/// inherent to `ByteStream`, so that every context type shares them, and the trait must be in scope
+/// to call them, such as with `use byte_slice::prelude::*;`.
+pub trait ByteContext {
+    /// Retrieve the most recent byte.
+    fn byte(&self) -> u8;
+
+    /// Retrieve the mark index.
+    fn mark_index(&self) -> usize;
+
+    /// Set the most recent byte.
+    fn set_byte(&mut self, byte: u8);
+
+    /// Set the mark index.
+    fn set_mark_index(&mut self, index: usize);
+
+    /// Set the stream index.
+    fn set_stream_index(&mut self, index: usize);
+
+    /// Retrieve the stream data.
+    fn stream(&self) -> &[u8];
+
+    /// Retrieve the stream index.
+    fn stream_index(&self) -> usize;
+
+    /// Retrieve the amount of readable bytes.
+    #[inline]
+    fn available(&self) -> usize {
+        self.stream().len() - self.index()
+    }
+
+    /// Collect all sequential digit bytes in `radix`, which must be between 2 and 36, and convert
+    /// them into an unsigned integer, enforcing `policy`.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in `radix`, `Collect::Eos`
+    /// upon locating end-of-stream, `Collect::Overflow` if an overflow would occur, and
+    /// `Collect::Violation` if a digit would violate `policy`, or if collection would finish with
+    /// fewer digits than `policy` requires. Each variant carries the value collected so far.
+    ///
+    /// Collection is not resumable. Use `resume_bounded()` when a number may span streams.
+    #[inline]
+    fn collect_bounded<T: UnsignedAccumulator>(&mut self, radix: u8, policy: &DigitPolicy)
+    -> Collect<T> {
+        self.resume_bounded(radix, policy, &mut NumberState::new(T::ZERO))
+    }
+
+    /// Collect all sequential digit bytes, and convert them into an unsigned integer.
+    ///
+    /// Returns `Collect::Done` upon locating a non-digit byte, `Collect::Eos` upon locating
+    /// end-of-stream, and `Collect::Overflow` if an overflow would occur. Each variant carries the
+    /// value collected so far.
+    ///
+    /// Eight digits are validated and converted at once while at least eight bytes are available
+    /// for reading.
+    fn collect_digits<T: UnsignedAccumulator>(&mut self) -> Collect<T> {
+        let mut value = T::ZERO;
+
+        while self.has_bytes(8) {
+            if let Some(v) = number::eight_digits(self.peek(8))
+                                   .and_then(|digits| value.checked_accumulate_eight(digits)) {
+                value = v;
+
+                self.jump(7);
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        loop {
+            if self.is_eos() && !self.fill() {
+                return Collect::Eos(value);
+            }
+
+            self.next();
+
+            if !is_digit!(self.byte()) {
+                return Collect::Done(value);
+            } else if let Some(v) = value.checked_accumulate(10, self.byte() - b'0') {
+                value = v;
+            } else {
+                return Collect::Overflow(value);
+            }
+        }
+    }
+
+    /// Collect a decimal float, and convert it into `T` with correct rounding.
+    ///
+    /// `format` determines which optional forms are accepted.
+    ///
+    /// Returns `FloatCollect::Done` upon locating a byte that is not part of the number,
+    /// `FloatCollect::Eos` upon locating end-of-stream, and `FloatCollect::Invalid` upon locating a
+    /// byte that cannot appear at its position within the number. In each case the most recent byte
+    /// is the last byte that was collected.
+    fn collect_float<T: FromStr>(&mut self, format: &FloatFormat) -> FloatCollect<T> {
+        let start       = self.index();
+        let mut scanned = number::scan_float(self.remaining(), format);
+
+        // the scan doesn't move the stream index, so it can start over once more bytes are filled
+        while let (_, FloatScan::Eos(_)) = scanned {
+            if !self.fill() {
+                break;
+            }
+
+            scanned = number::scan_float(self.remaining(), format);
+        }
+
+        let (length, scan) = scanned;
+
+        match scan {
+            FloatScan::Eos(complete) => {
+                if length > 0 {
+                    self.jump(length - 1);
+                    self.next();
+                }
+
+                if complete {
+                    FloatCollect::Eos(number::parse_float(&self.stream()[start..self.index()]))
+                } else {
+                    FloatCollect::Eos(None)
+                }
+            },
+            FloatScan::Done => {
+                self.jump(length);
+                self.next();
+
+                match number::parse_float(&self.stream()[start..self.index() - 1]) {
+                    Some(value) => FloatCollect::Done(value),
+                    None => FloatCollect::Invalid
+                }
+            },
+            FloatScan::Invalid => {
+                self.jump(length);
+                self.next();
+
+                FloatCollect::Invalid
+            }
+        }
+    }
+
+    /// Collect all sequential hex bytes, and convert them into an unsigned integer.
+    ///
+    /// Returns `Collect::Done` upon locating a non-hex byte, `Collect::Eos` upon locating
+    /// end-of-stream, and `Collect::Overflow` if an overflow would occur. Each variant carries the
+    /// value collected so far.
+    #[inline]
+    fn collect_hex<T: UnsignedAccumulator>(&mut self) -> Collect<T> {
+        self.collect_radix(16)
+    }
+
+    /// Collect an unsigned integer, detecting its radix from a `0x`, `0o` or `0b` prefix, and
+    /// defaulting to decimal when no prefix is present.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in the detected radix,
+    /// `Collect::Eos` upon locating end-of-stream, and `Collect::Overflow` if an overflow would
+    /// occur. Each variant carries the value collected so far.
+    ///
+    /// Collection is not resumable. Use `resume_prefixed()` when a number may span streams.
+    #[inline]
+    fn collect_prefixed<T: UnsignedAccumulator>(&mut self) -> Collect<T> {
+        self.resume_prefixed(&mut NumberState::new(T::ZERO))
+    }
+
+    /// Collect all sequential digit bytes in `radix`, which must be between 2 and 36, and convert
+    /// them into an unsigned integer.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in `radix`, `Collect::Eos`
+    /// upon locating end-of-stream, and `Collect::Overflow` if an overflow would occur. Each
+    /// variant carries the value collected so far.
+    #[inline]
+    fn collect_radix<T: UnsignedAccumulator>(&mut self, radix: u8) -> Collect<T> {
+        self.resume_radix(radix, &mut NumberState::new(T::ZERO))
+    }
+
+    /// Collect an optional `+` or `-` sign followed by all sequential digit bytes, and convert them
+    /// into a signed integer.
+    ///
+    /// Returns `Collect::Done` upon locating a non-digit byte, `Collect::Eos` upon locating
+    /// end-of-stream, and `Collect::Overflow` if an overflow would occur. Each variant carries the
+    /// value collected so far.
+    #[inline]
+    fn collect_signed<T: SignedAccumulator>(&mut self) -> Collect<T> {
+        self.resume_signed(&mut NumberState::new(T::ZERO))
+    }
+
+    /// Collect bytes until `length` bytes have been collected since the mark index.
+    ///
+    /// Returns `false` upon locating end-of-stream.
+    fn collect_length(&mut self, length: usize) -> bool {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return false;
+            }
+
+            self.next();
+
+            if self.index() == self.mark_index() + length {
+                return true;
+            }
+        }
+    }
+
+    /// Collect while `when` yields `true`.
+    ///
+    /// Returns `false` upon locating end-of-stream.
+    fn collect_when<F: FnMut(u8) -> bool>(&mut self, mut when: F) -> bool {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return false;
+            }
+
+            self.next();
+
+            if !when(self.byte()) {
+                return true;
+            }
+        }
+    }
+
+    /// Collect while the bytes are in `set`, and return the length of the run.
+    ///
+    /// The run is located using `ByteSet::span()`, and the byte that ends it is collected, so that
+    /// the most recent byte is set to it. Returns `Collect::Eos` upon locating end-of-stream.
+    fn collect_set(&mut self, set: &ByteSet) -> Collect<usize> {
+        let length = self.span_set(set);
+        let index  = self.index() + length;
+
+        if index == self.stream().len() {
+            if length > 0 {
+                let byte = self.stream()[index - 1];
+
+                self.set_byte(byte);
+            }
+
+            self.set_stream_index(index);
+
+            Collect::Eos(length)
+        } else {
+            let byte = self.stream()[index];
+
+            self.set_byte(byte);
+            self.set_stream_index(index + 1);
+
+            Collect::Done(length)
+        }
+    }
+
+    /// Collect while `until` yields `false`.
+    ///
+    /// Returns `false` upon locating end-of-stream.
+    fn collect_until<F: FnMut(u8) -> bool>(&mut self, mut until: F) -> bool {
+        self.collect_when(|byte| !until(byte))
+    }
+
+    /// Count each occurrence of `byte` starting at the stream index until end-of-stream.
+    fn count(&self, byte: u8) -> usize {
+        let mut count = 0;
+
+        for n in self.index()..self.stream().len() {
+            if self.stream()[n] == byte {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Count each byte starting at the stream index until end-of-stream, if `when` yields `true`.
+    ///
+    /// The most recent byte is set to each byte before `when` is called with the context, and is
+    /// left set to the byte at the stream index, if any.
+    fn count_when<F: FnMut(&Self) -> bool>(&mut self, mut when: F) -> usize {
+        let mut count = 0;
+
+        for n in self.index()..self.stream().len() {
+            let next = self.stream()[n];
+
+            self.set_byte(next);
+
+            if when(self) {
+                count += 1;
+            }
+        }
+
+        if let Some(&byte) = self.stream().get(self.index()) {
+            self.set_byte(byte);
+        }
+
+        count
+    }
+
+    /// Fill the stream until `length` bytes are available for reading.
+    ///
+    /// Returns `false` if the stream runs out of bytes first.
+    fn ensure(&mut self, length: usize) -> bool {
+        while !self.has_bytes(length) {
+            if !self.fill() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Fill the stream with more bytes, and indicate that any were added.
+    ///
+    /// The collectors call this upon locating end-of-stream, and only finish with end-of-stream
+    /// when it yields `false`. A stream over a single slice has no more bytes, which is the
+    /// default.
+    #[inline]
+    fn fill(&mut self) -> bool {
+        false
+    }
+
+    /// Find the first occurrence of `byte` and return its index.
+    #[inline]
+    fn find(&self, byte: u8) -> Option<usize> {
+        self.find_from(0, byte)
+    }
+
+    /// Find the first occurrence of `byte` and return its index, filling the stream until it's
+    /// found or the stream runs out of bytes.
+    fn find_fill(&mut self, byte: u8) -> Option<usize> {
+        let mut start = 0;
+
+        loop {
+            if let Some(index) = self.find_from(start, byte) {
+                return Some(index);
+            }
+
+            start = self.available();
+
+            if !self.fill() {
+                return None;
+            }
+        }
+    }
+
+    /// Find the first occurrence of `byte` and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find_from(&self, start: usize, byte: u8) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            search::memchr(byte, &self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Find the first occurrence of `a` or `b` and return its index.
+    #[inline]
+    fn find2(&self, a: u8, b: u8) -> Option<usize> {
+        self.find2_from(0, a, b)
+    }
+
+    /// Find the first occurrence of `a` or `b` and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find2_from(&self, start: usize, a: u8, b: u8) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            search::memchr2(a, b, &self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Find the first occurrence of `a`, `b` or `c` and return its index.
+    #[inline]
+    fn find3(&self, a: u8, b: u8, c: u8) -> Option<usize> {
+        self.find3_from(0, a, b, c)
+    }
+
+    /// Find the first occurrence of `a`, `b` or `c` and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find3_from(&self, start: usize, a: u8, b: u8, c: u8) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            search::memchr3(a, b, c, &self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Find the leftmost occurrence of any pattern of `finder`.
+    #[inline]
+    fn find_multi(&self, finder: &MultiFinder) -> Option<Match> {
+        self.find_multi_from(0, finder)
+    }
+
+    /// Find the leftmost occurrence of any pattern of `finder`.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find_multi_from(&self, start: usize, finder: &MultiFinder) -> Option<Match> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            finder.find(&self.stream()[offset..]).map(|m| Match{
+                end:     m.end + offset,
+                pattern: m.pattern,
+                start:   m.start + offset
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Find the first occurrence of `pattern` and return its index.
+    #[inline]
+    fn find_pattern(&self, pattern: &[u8]) -> Option<usize> {
+        self.find_pattern_from(0, pattern)
+    }
+
+    /// Find the first occurrence of `pattern` and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    ///
+    /// A `Finder` is built for `pattern` on each call. When a pattern is searched for repeatedly, a
+    /// precompiled `Finder` given to `find_with()` avoids this.
+    fn find_pattern_from(&self, start: usize, pattern: &[u8]) -> Option<usize> {
+        self.find_with_from(start, &Finder::new(pattern))
+    }
+
+    /// Find the first occurrence of `pattern`, ignoring ASCII case, and return its index.
+    #[inline]
+    fn find_pattern_ignore_case(&self, pattern: &[u8]) -> Option<usize> {
+        self.find_pattern_ignore_case_from(0, pattern)
+    }
+
+    /// Find the first occurrence of `pattern`, ignoring ASCII case, and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find_pattern_ignore_case_from(&self, start: usize, pattern: &[u8]) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            search::find_ignore_case(pattern, &self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Find the first occurrence of the pattern of `finder` and return its index.
+    #[inline]
+    fn find_with(&self, finder: &Finder) -> Option<usize> {
+        self.find_with_from(0, finder)
+    }
+
+    /// Find the first occurrence of the pattern of `finder` and return its index.
+    ///
+    /// `start` is the starting index relative to the stream index.
+    fn find_with_from(&self, start: usize, finder: &Finder) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            finder.find(&self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Indicates that a specified amount of bytes are available for reading.
+    #[inline]
+    fn has_bytes(&self, length: usize) -> bool {
+        self.index() + length <= self.stream().len()
+    }
+
+    /// Retrieve the current stream index.
+    #[inline]
+    fn index(&self) -> usize {
+        self.stream_index()
+    }
+
+    /// Indicates that we're at the end of the stream.
+    #[inline]
+    fn is_eos(&self) -> bool {
+        self.index() == self.stream().len()
+    }
+
+    /// Jump `length` bytes.
+    ///
+    /// This method assumes that `length` bytes are available for reading.
+    #[inline]
+    fn jump(&mut self, length: usize) {
+        let index = self.index() + length;
+
+        self.set_stream_index(index);
+    }
+
+    /// Set the mark index to the current stream index.
+    #[inline]
+    fn mark(&mut self) {
+        let index = self.index();
+
+        self.set_mark_index(index);
+    }
+
+    /// Match the remaining stream against `keywords`, and return the index of the longest keyword
+    /// that it starts with.
+    ///
+    /// On a match, the stream index is advanced past the keyword and the most recent byte is set to
+    /// its last byte. Otherwise the stream is left untouched.
+    #[inline]
+    fn match_keyword<const N: usize>(&mut self, keywords: &Keywords<N>) -> Option<usize> {
+        let n       = keywords.find(self.remaining())?;
+        let keyword = keywords.keywords()[n];
+        let index   = self.index() + keyword.len();
+
+        self.set_byte(keyword[keyword.len() - 1]);
+        self.set_stream_index(index);
+
+        Some(n)
+    }
+
+    /// Advance the stream index one byte and set the most recent byte to the new byte.
+    ///
+    /// Panics if no byte is available for reading.
+    #[inline]
+    fn next(&mut self) {
+        let byte  = self.stream()[self.index()];
+        let index = self.index() + 1;
+
+        self.set_byte(byte);
+        self.set_stream_index(index);
+    }
+
+    /// Advance the stream index one byte and set the most recent byte to the new byte, without
+    /// checking that a byte is available.
+    ///
+    /// # Safety
+    ///
+    /// A byte must be available for reading. With the `debug-checks` feature enabled, this is
+    /// asserted in debug builds.
+    #[inline]
+    unsafe fn next_unchecked(&mut self) {
+        #[cfg(feature = "debug-checks")]
+        debug_assert!(!self.is_eos(), "no byte is available at index {}", self.index());
+
+        let byte  = *self.stream().get_unchecked(self.index());
+        let index = self.index() + 1;
+
+        self.set_byte(byte);
+        self.set_stream_index(index);
+    }
+
+    /// Peek at a slice of bytes.
+    ///
+    /// This method assumes that `length` bytes are available for reading.
+    #[inline]
+    fn peek(&self, length: usize) -> &[u8] {
+        &self.stream()[self.index()..self.index() + length]
+    }
+
+    /// Retrieve the remaining available bytes.
+    #[inline]
+    fn remaining(&self) -> &[u8] {
+        &self.stream()[self.index()..]
+    }
+
+    /// Resume collecting all sequential digit bytes in `radix`, which must be between 2 and 36,
+    /// into `state`, enforcing `policy`.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in `radix`, `Collect::Eos`
+    /// upon locating end-of-stream, `Collect::Overflow` if an overflow would occur, and
+    /// `Collect::Violation` if a digit would violate `policy`, or if collection would finish with
+    /// fewer digits than `policy` requires. Each variant carries the value collected so far. Upon
+    /// `Collect::Eos`, `state` can be used to resume collection with the next stream.
+    fn resume_bounded<T: UnsignedAccumulator>(&mut self, radix: u8, policy: &DigitPolicy,
+                                              state: &mut NumberState<T>) -> Collect<T> {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return Collect::Eos(state.value);
+            }
+
+            self.next();
+
+            if let Some(digit) = number::radix_value(self.byte(), radix) {
+                let leading_zero = if state.digits == 0 { digit == 0 } else { state.leading_zero };
+
+                if !policy.allows(state.digits + 1, leading_zero) {
+                    return Collect::Violation(state.value);
+                } else if let Some(value) = state.value.checked_accumulate(radix, digit) {
+                    state.digits       += 1;
+                    state.leading_zero  = leading_zero;
+                    state.value         = value;
+                } else {
+                    return Collect::Overflow(state.value);
+                }
+            } else if policy.is_satisfied(state.digits) {
+                return Collect::Done(state.value);
+            } else {
+                return Collect::Violation(state.value);
+            }
+        }
+    }
+
+    /// Resume collecting an unsigned integer into `state`, detecting its radix from a `0x`, `0o`
+    /// or `0b` prefix, and defaulting to decimal when no prefix is present.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in the detected radix,
+    /// `Collect::Eos` upon locating end-of-stream, and `Collect::Overflow` if an overflow would
+    /// occur. Each variant carries the value collected so far. Upon `Collect::Eos`, `state` can be
+    /// used to resume collection with the next stream.
+    fn resume_prefixed<T: UnsignedAccumulator>(&mut self, state: &mut NumberState<T>)
+    -> Collect<T> {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return Collect::Eos(state.value);
+            }
+
+            self.next();
+
+            if state.digits == 1 && state.leading_zero && state.radix == 0 {
+                if let Some(prefix) = number::prefix_radix(self.byte()) {
+                    state.radix = prefix;
+
+                    continue;
+                }
+            }
+
+            let radix = if state.radix == 0 { 10 } else { state.radix };
+
+            if let Some(digit) = number::radix_value(self.byte(), radix) {
+                if let Some(value) = state.value.checked_accumulate(radix, digit) {
+                    state.leading_zero  = state.leading_zero || (state.digits == 0 && digit == 0);
+                    state.digits       += 1;
+                    state.value         = value;
+                } else {
+                    return Collect::Overflow(state.value);
+                }
+            } else {
+                return Collect::Done(state.value);
+            }
+        }
+    }
+
+    /// Resume collecting all sequential digit bytes in `radix`, which must be between 2 and 36,
+    /// into `state`.
+    ///
+    /// Returns `Collect::Done` upon locating a byte that is not a digit in `radix`, `Collect::Eos`
+    /// upon locating end-of-stream, and `Collect::Overflow` if an overflow would occur. Each
+    /// variant carries the value collected so far. Upon `Collect::Eos`, `state` can be used to
+    /// resume collection with the next stream.
+    fn resume_radix<T: UnsignedAccumulator>(&mut self, radix: u8, state: &mut NumberState<T>)
+    -> Collect<T> {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return Collect::Eos(state.value);
+            }
+
+            self.next();
+
+            if let Some(digit) = number::radix_value(self.byte(), radix) {
+                if let Some(value) = state.value.checked_accumulate(radix, digit) {
+                    state.digits += 1;
+                    state.value   = value;
+                } else {
+                    return Collect::Overflow(state.value);
+                }
+            } else {
+                return Collect::Done(state.value);
+            }
+        }
+    }
+
+    /// Resume collecting an optional `+` or `-` sign followed by all sequential digit bytes into
+    /// `state`.
+    ///
+    /// Returns `Collect::Done` upon locating a non-digit byte, `Collect::Eos` upon locating
+    /// end-of-stream, and `Collect::Overflow` if an overflow would occur. Each variant carries the
+    /// value collected so far. Upon `Collect::Eos`, `state` can be used to resume collection with
+    /// the next stream.
+    fn resume_signed<T: SignedAccumulator>(&mut self, state: &mut NumberState<T>) -> Collect<T> {
+        loop {
+            if self.is_eos() && !self.fill() {
+                return Collect::Eos(state.value);
+            }
+
+            self.next();
+
+            if !state.is_started() && (self.byte() == b'-' || self.byte() == b'+') {
+                state.negative = self.byte() == b'-';
+                state.signed   = true;
+            } else if !is_digit!(self.byte()) {
+                return Collect::Done(state.value);
+            } else if let Some(value) = if state.negative {
+                state.value.checked_accumulate_negative(10, self.byte() - b'0')
+            } else {
+                state.value.checked_accumulate(10, self.byte() - b'0')
+            } {
+                state.digits += 1;
+                state.value   = value;
+            } else {
+                return Collect::Overflow(state.value);
+            }
+        }
+    }
+
+    /// Find the last occurrence of `byte` and return its index.
+    #[inline]
+    fn rfind(&self, byte: u8) -> Option<usize> {
+        self.rfind_from(0, byte)
+    }
+
+    /// Find the last occurrence of `byte` and return its index.
+    ///
+    /// `start` is the index relative to the stream index where the search ends.
+    fn rfind_from(&self, start: usize, byte: u8) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            search::memrchr(byte, &self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Find the last occurrence of `byte` before the stream index, searching backwards through the
+    /// bytes that have already been read, and return its index.
+    fn rfind_before(&self, byte: u8) -> Option<usize> {
+        let index = self.index().min(self.stream().len());
+
+        search::memrchr(byte, &self.stream()[..index])
+    }
+
+    /// Find the last occurrence of `pattern` and return its index.
+    #[inline]
+    fn rfind_pattern(&self, pattern: &[u8]) -> Option<usize> {
+        self.rfind_pattern_from(0, pattern)
+    }
+
+    /// Find the last occurrence of `pattern` and return its index.
+    ///
+    /// `start` is the index relative to the stream index where the search ends.
+    ///
+    /// A `FinderRev` is built for `pattern` on each call. When a pattern is searched for
+    /// repeatedly, a precompiled `FinderRev` given to `rfind_with()` avoids this.
+    fn rfind_pattern_from(&self, start: usize, pattern: &[u8]) -> Option<usize> {
+        self.rfind_with_from(start, &FinderRev::new(pattern))
+    }
+
+    /// Find the last occurrence of `pattern` that ends at or before the stream index, searching
+    /// backwards through the bytes that have already been read, and return its index.
+    fn rfind_pattern_before(&self, pattern: &[u8]) -> Option<usize> {
+        let index = self.index().min(self.stream().len());
+
+        FinderRev::new(pattern).rfind(&self.stream()[..index])
+    }
+
+    /// Find the last occurrence of the pattern of `finder` and return its index.
+    #[inline]
+    fn rfind_with(&self, finder: &FinderRev) -> Option<usize> {
+        self.rfind_with_from(0, finder)
+    }
+
+    /// Find the last occurrence of the pattern of `finder` and return its index.
+    ///
+    /// `start` is the index relative to the stream index where the search ends.
+    fn rfind_with_from(&self, start: usize, finder: &FinderRev) -> Option<usize> {
+        let offset = self.index() + start;
+
+        if offset < self.stream().len() {
+            finder.rfind(&self.stream()[offset..]).map(|n| n + offset)
+        } else {
+            None
+        }
+    }
+
+    /// Replay the most recent byte, but do not change the most recent byte.
+    #[inline]
+    fn replay(&mut self) {
+        self.rewind(1);
+    }
+
+    /// Rewind the stream index by `length` bytes, but do not change the most recent byte.
+    #[inline]
+    fn rewind(&mut self, length: usize) {
+        let index = self.index() - length;
+
+        self.set_stream_index(index);
+    }
+
+    /// Rewind the stream index to `index`, but do not change the most recent byte.
+    #[inline]
+    fn rewind_to(&mut self, index: usize) {
+        self.set_stream_index(index);
+    }
+
+    /// Retrieve the slice of marked bytes.
+    #[inline]
+    fn slice(&self) -> &[u8] {
+        &self.stream()[self.mark_index()..self.index()]
+    }
+
+    /// Retrieve the slice of marked bytes ignoring the very last byte.
+    #[inline]
+    fn slice_ignore(&self) -> &[u8] {
+        &self.stream()[self.mark_index()..self.index() - 1]
+    }
+
+    /// Retrieve the length of marked bytes.
+    #[inline]
+    fn slice_length(&self) -> usize {
+        self.index() - self.mark_index()
+    }
+
+    /// Skip the run of bytes in `set`, stopping before the first byte that isn't, and return the
+    /// length of the run.
+    ///
+    /// The most recent byte is set to the last byte of the run, if any.
+    fn skip_set(&mut self, set: &ByteSet) -> usize {
+        let length = self.span_set(set);
+        let index  = self.index() + length;
+
+        if length > 0 {
+            let byte = self.stream()[index - 1];
+
+            self.set_byte(byte);
+            self.set_stream_index(index);
+        }
+
+        length
+    }
+
+    /// Retrieve the length of the run of bytes in `set` at the stream index, filling the stream
+    /// while the run reaches end-of-stream.
+    fn span_set(&mut self, set: &ByteSet) -> usize {
+        let mut length = 0;
+
+        loop {
+            length += set.span(&self.stream()[self.index() + length..]);
+
+            if self.index() + length < self.stream().len() || !self.fill() {
+                return length;
+            }
+        }
+    }
+
+    /// Determine if the remaining stream starts with `pattern`.
+    ///
+    /// This method assumes that `pattern.len()` bytes are available for reading.
+    #[inline]
+    fn starts_with(&self, pattern: &[u8]) -> bool {
+        &self.stream()[self.index()..self.index() + pattern.len()] == pattern
+    }
+
+    /// Determine if the remaining stream starts with `pattern`, ignoring ASCII case.
+    ///
+    /// This method assumes that `pattern.len()` bytes are available for reading.
+    #[inline]
+    fn starts_with_ignore_case(&self, pattern: &[u8]) -> bool {
+        search::eq_ignore_case(&self.stream()[self.index()..self.index() + pattern.len()], pattern)
+    }
+
+    /// Advance the stream index one byte and set the most recent byte to the new byte, returning
+    /// the byte, or `None` at the end of the stream.
+    #[inline]
+    fn try_next(&mut self) -> Option<u8> {
+        if self.is_eos() && !self.fill() {
+            return None;
+        }
+
+        self.next();
+
+        Some(self.byte())
+    }
+
+    /// Determine if the remaining stream starts with the fixed-length `pattern`, comparing a word
+    /// at a time.
+    ///
+    /// Returns `false` when fewer than `N` bytes are available.
+    #[inline]
+    fn try_starts_with<const N: usize>(&self, pattern: &[u8; N]) -> bool {
+        match self.stream().get(self.index()..).and_then(|bytes| bytes.get(..N)) {
+            Some(bytes) => unsafe { search::starts_with_unchecked(bytes, 0, pattern) },
+            None        => false
+        }
+    }
+}
+
+/// Byte context over a mutable stream, with a write index that trails the stream index.
+///
+/// Decoded output is written at the write index, overwriting bytes that have already been read, so
+/// that percent-decoding, unescaping and similar transformations can be done in place. The write
+/// index must never pass the stream index.
+pub trait ByteContextMut: ByteContext {
+    /// Set the write index.
+    fn set_write_index(&mut self, index: usize);
+
+    /// Retrieve the mutable stream.
+    fn stream_mut(&mut self) -> &mut [u8];
+
+    /// Retrieve the write index.
+    fn write_index(&self) -> usize;
+
+    /// Copy the marked bytes to the write index, advance the write index past them, and set the
+    /// mark index to the stream index.
+    #[inline]
+    fn compact(&mut self) {
+        let end = self.index();
+
+        self.compact_range(end);
+    }
+
+    /// Copy the marked bytes, ignoring the very last byte, to the write index, advance the write
+    /// index past them, and set the mark index to the stream index.
+    #[inline]
+    fn compact_ignore(&mut self) {
+        let end = self.index() - 1;
+
+        self.compact_range(end);
+    }
+
+    /// Copy the bytes from the mark index to `end` to the write index, advance the write index past
+    /// them, and set the mark index to the stream index.
+    ///
+    /// Panics if the write index would pass the stream index.
+    fn compact_range(&mut self, end: usize) {
+        let mark  = self.mark_index();
+        let index = self.index();
+        let write = self.write_index();
+
+        assert!(mark <= end && write + (end - mark) <= index,
+                "write index {} would pass stream index {}", write + end.saturating_sub(mark),
+                index);
+
+        self.stream_mut().copy_within(mark..end, write);
+        self.set_write_index(write + end - mark);
+        self.set_mark_index(index);
+    }
+
+    /// Write `byte` at the write index, and advance the write index one byte.
+    ///
+    /// Panics if the write index would pass the stream index.
+    #[inline]
+    fn write_byte(&mut self, byte: u8) {
+        let write = self.write_index();
+
+        assert!(write < self.index(), "write index {} would pass stream index {}", write,
+                self.index());
+
+        self.stream_mut()[write] = byte;
+        self.set_write_index(write + 1);
+    }
+
+    /// Retrieve the written bytes.
+    #[inline]
+    fn written(&self) -> &[u8] {
+        &self.stream()[..self.write_index()]
+    }
+}
+
+/// Default byte stream type.
+///
+/// The stream methods are provided by `ByteContext`:
+///
+/// ```
+/// use byte_slice::{ ByteContext, ByteStream };
+///
+/// let mut stream = ByteStream::new(b"GET /");
+///
+/// assert_eq!(stream.find(b' '), Some(3));
+///
+/// stream.collect_until(|byte| byte == b' ');
+///
+/// assert_eq!(stream.slice_ignore(), b"GET");
+/// ```
 pub struct ByteStream<'a> {
     /// Current byte.
     pub byte: u8,
@@ -59,6 +1113,133 @@ impl<'a> ByteStream<'a> {
     }
 }
 
+impl<'a> ByteContext for ByteStream<'a> {
+    #[inline]
+    fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    #[inline]
+    fn mark_index(&self) -> usize {
+        self.mark_index
+    }
+
+    #[inline]
+    fn set_byte(&mut self, byte: u8) {
+        self.byte = byte;
+    }
+
+    #[inline]
+    fn set_mark_index(&mut self, index: usize) {
+        self.mark_index = index;
+    }
+
+    #[inline]
+    fn set_stream_index(&mut self, index: usize) {
+        self.stream_index = index;
+    }
+
+    #[inline]
+    fn stream(&self) -> &[u8] {
+        self.stream
+    }
+
+    #[inline]
+    fn stream_index(&self) -> usize {
+        self.stream_index
+    }
+}
+
+/// Byte stream type over a mutable slice, with a write index for in-place decoding.
+pub struct ByteStreamMut<'a> {
+    /// Current byte.
+    pub byte: u8,
+
+    /// Mark index.
+    pub mark_index: usize,
+
+    /// Stream data.
+    pub stream: &'a mut [u8],
+
+    /// Stream index.
+    pub stream_index: usize,
+
+    /// Write index.
+    pub write_index: usize
+}
+
+impl<'a> ByteStreamMut<'a> {
+    /// Create a new `ByteStreamMut`.
+    pub fn new(stream: &'a mut [u8]) -> ByteStreamMut<'a> {
+        ByteStreamMut{
+            byte:         0,
+            mark_index:   0,
+            stream:       stream,
+            stream_index: 0,
+            write_index:  0
+        }
+    }
+
+    /// Convert this stream into the written bytes.
+    pub fn into_written(self) -> &'a mut [u8] {
+        &mut self.stream[..self.write_index]
+    }
+}
+
+impl<'a> ByteContext for ByteStreamMut<'a> {
+    #[inline]
+    fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    #[inline]
+    fn mark_index(&self) -> usize {
+        self.mark_index
+    }
+
+    #[inline]
+    fn set_byte(&mut self, byte: u8) {
+        self.byte = byte;
+    }
+
+    #[inline]
+    fn set_mark_index(&mut self, index: usize) {
+        self.mark_index = index;
+    }
+
+    #[inline]
+    fn set_stream_index(&mut self, index: usize) {
+        self.stream_index = index;
+    }
+
+    #[inline]
+    fn stream(&self) -> &[u8] {
+        self.stream
+    }
+
+    #[inline]
+    fn stream_index(&self) -> usize {
+        self.stream_index
+    }
+}
+
+impl<'a> ByteContextMut for ByteStreamMut<'a> {
+    #[inline]
+    fn set_write_index(&mut self, index: usize) {
+        self.write_index = index;
+    }
+
+    #[inline]
+    fn stream_mut(&mut self) -> &mut [u8] {
+        self.stream
+    }
+
+    #[inline]
+    fn write_index(&self) -> usize {
+        self.write_index
+    }
+}
+
 impl<'a> fmt::Debug for ByteStream<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         if is_visible_8bit!(self.byte) || self.byte == 0x20 || self.byte == 0xFF {
@@ -82,3 +1263,22 @@ impl<'a> fmt::Display for ByteStream<'a> {
         }
     }
 }
+
+impl<'a> fmt::Debug for ByteStreamMut<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ByteStreamMut({})", self)
+    }
+}
+
+impl<'a> fmt::Display for ByteStreamMut<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if is_visible_8bit!(self.byte) || self.byte == 0x20 || self.byte == 0xFF {
+            write!(formatter, "byte[{}]='{}', mark_index={}, stream_index={}, write_index={}",
+                   self.byte, self.byte as char, self.mark_index, self.stream_index,
+                   self.write_index)
+        } else {
+            write!(formatter, "byte[{}]='', mark_index={}, stream_index={}, write_index={}",
+                   self.byte, self.mark_index, self.stream_index, self.write_index)
+        }
+    }
+}
@@ -22,7 +22,9 @@
 #[macro_export]
 macro_rules! bs_available {
     ($context:expr) => ({
-        $context.stream.len() - bs_index!($context)
+        use $crate::ByteContext;
+
+        $context.available()
     });
 }
 
@@ -32,7 +34,7 @@ macro_rules! bs_available {
 macro_rules! bs_collect {
     ($context:expr, $on_byte:expr, $on_eos:expr) => ({
         loop {
-            if bs_is_eos!($context) {
+            if bs_is_eos!($context) && !bs_fill!($context) {
                 $on_eos
             } else {
                 bs_next!($context);
@@ -43,59 +45,89 @@ macro_rules! bs_collect {
     });
 }
 
-/// Collect all sequential digit bytes into `$var` (u8), and convert them into an unsigned integer.
-/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
-/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+/// Collect all sequential digit bytes in `$radix` into `$var`, and convert them into an unsigned
+/// integer of type `$acc`, enforcing the `DigitPolicy` given as `$policy`. `$radix` must be between
+/// 2 and 36. If `$on_byte` is supplied, for each new digit byte execute `$on_byte`. Upon locating
+/// end-of-stream execute `$on_eos`. If an overflow would occur, execute `$on_overflow`. If a digit
+/// would violate `$policy`, or if collection would finish with fewer digits than `$policy`
+/// requires, execute `$on_violation`.
 ///
-/// Exit the collection loop upon locating a non-digit byte.
+/// `$acc` may be any type implementing `UnsignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in `$radix`.
 #[macro_export]
-macro_rules! bs_collect_digits8 {
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+macro_rules! bs_collect_bounded {
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $policy:expr, $on_byte:expr, $on_overflow:expr,
+     $on_violation:expr, $on_eos:expr, $ty:ty) => ({
+        use $crate::ByteContext;
+
+        let mut digits       = 0;
+        let mut leading_zero = false;
+        let policy: $crate::DigitPolicy = $policy;
+        let radix: u8 = $radix;
+
         bs_collect!($context,
-            if is_digit!($context.byte) {
-                if let Some(value) = ($var as u8).checked_mul(10) {
-                    if let Some(value) = value.checked_add($context.byte - b'0') {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow;
-                    }
+            if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                digits       += 1;
+                leading_zero  = leading_zero || (digits == 1 && digit == 0);
+
+                if !policy.allows(digits, leading_zero) {
+                    $on_violation
+                } else if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $var as $acc, radix, digit
+                ) {
+                    $var = value as $ty;
+                    $on_byte
                 } else {
-                    $on_overflow;
+                    $on_overflow
                 }
-            } else {
+            } else if policy.is_satisfied(digits) {
                 break;
+            } else {
+                $on_violation
             },
             $on_eos
         );
     });
 
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits8!($context, $var, $on_byte, $on_overflow, $on_eos, u8);
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $policy:expr, $on_byte:expr, $on_overflow:expr,
+     $on_violation:expr, $on_eos:expr) => ({
+        bs_collect_bounded!($context, $var, $acc, $radix, $policy, $on_byte, $on_overflow,
+                            $on_violation, $on_eos, $acc);
     });
 
-    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits8!($context, $var, {}, $on_overflow, $on_eos, u8);
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $policy:expr, $on_overflow:expr,
+     $on_violation:expr, $on_eos:expr) => ({
+        bs_collect_bounded!($context, $var, $acc, $radix, $policy, {}, $on_overflow, $on_violation,
+                            $on_eos, $acc);
     });
 }
 
-/// Collect all sequential digit bytes into `$var` (u16), and convert them into an unsigned integer.
-/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
-/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+/// Collect all sequential digit bytes into `$var`, and convert them into an unsigned integer of
+/// type `$acc`. If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating
+/// end-of-stream execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// `$acc` may be any type implementing `UnsignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// When `$on_byte` is not supplied, eight digits are validated and converted at once while at
+/// least eight bytes are available for reading.
 ///
 /// Exit the collection loop upon locating a non-digit byte.
 #[macro_export]
-macro_rules! bs_collect_digits16 {
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+macro_rules! bs_collect_digits {
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr,
+     $ty:ty) => ({
+        use $crate::ByteContext;
+
         bs_collect!($context,
-            if is_digit!($context.byte) {
-                if let Some(value) = ($var as u16).checked_mul(10) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u16) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+            if is_digit!($context.byte()) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $var as $acc, 10, $context.byte() - b'0'
+                ) {
+                    $var = value as $ty;
+                    $on_byte
                 } else {
                     $on_overflow
                 }
@@ -106,12 +138,70 @@ macro_rules! bs_collect_digits16 {
         );
     });
 
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_digits!($context, $var, $acc, $on_byte, $on_overflow, $on_eos, $acc);
+    });
+
+    ($context:expr, $var:expr, $acc:ty, $on_overflow:expr, $on_eos:expr) => ({
+        // without $on_byte, collect eight digits at a time while they are available
+        while bs_has_bytes!($context, 8) {
+            if let Some(digits) = $crate::number::eight_digits(bs_peek!($context, 8)) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate_eight(
+                    $var as $acc, digits
+                ) {
+                    $var = value;
+
+                    bs_jump!($context, 7);
+                    bs_next!($context);
+
+                    continue;
+                }
+            }
+
+            break;
+        }
+
+        bs_collect_digits!($context, $var, $acc, {}, $on_overflow, $on_eos, $acc);
+    });
+}
+
+/// Collect all sequential digit bytes into `$var` (u8), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// Exit the collection loop upon locating a non-digit byte.
+#[macro_export]
+macro_rules! bs_collect_digits8 {
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+        bs_collect_digits!($context, $var, u8, $on_byte, $on_overflow, $on_eos, $ty);
+    });
+
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_digits!($context, $var, u8, $on_byte, $on_overflow, $on_eos, u8);
+    });
+
+    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_digits!($context, $var, u8, $on_overflow, $on_eos);
+    });
+}
+
+/// Collect all sequential digit bytes into `$var` (u16), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// Exit the collection loop upon locating a non-digit byte.
+#[macro_export]
+macro_rules! bs_collect_digits16 {
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+        bs_collect_digits!($context, $var, u16, $on_byte, $on_overflow, $on_eos, $ty);
+    });
+
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits16!($context, $var, $on_byte, $on_overflow, $on_eos, u16);
+        bs_collect_digits!($context, $var, u16, $on_byte, $on_overflow, $on_eos, u16);
     });
 
     ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits16!($context, $var, {}, $on_overflow, $on_eos, u16);
+        bs_collect_digits!($context, $var, u16, $on_overflow, $on_eos);
     });
 }
 
@@ -123,31 +213,15 @@ macro_rules! bs_collect_digits16 {
 #[macro_export]
 macro_rules! bs_collect_digits32 {
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
-        bs_collect!($context,
-            if is_digit!($context.byte) {
-                if let Some(value) = ($var as u32).checked_mul(10) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u32) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else {
-                break;
-            },
-            $on_eos
-        );
+        bs_collect_digits!($context, $var, u32, $on_byte, $on_overflow, $on_eos, $ty);
     });
 
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits32!($context, $var, $on_byte, $on_overflow, $on_eos, u32);
+        bs_collect_digits!($context, $var, u32, $on_byte, $on_overflow, $on_eos, u32);
     });
 
     ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits32!($context, $var, {}, $on_overflow, $on_eos, u32);
+        bs_collect_digits!($context, $var, u32, $on_overflow, $on_eos);
     });
 }
 
@@ -159,15 +233,74 @@ macro_rules! bs_collect_digits32 {
 #[macro_export]
 macro_rules! bs_collect_digits64 {
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+        bs_collect_digits!($context, $var, u64, $on_byte, $on_overflow, $on_eos, $ty);
+    });
+
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_digits!($context, $var, u64, $on_byte, $on_overflow, $on_eos, u64);
+    });
+
+    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_digits!($context, $var, u64, $on_overflow, $on_eos);
+    });
+}
+
+/// Collect a decimal float starting at `$context.stream_index` into `$var`, and convert it into
+/// `$ty` (f32 or f64). `$format` is a `FloatFormat` that determines which optional forms are
+/// accepted. Upon locating end-of-stream execute `$on_eos`, after storing the value in `$var` if
+/// the collected bytes form a complete number. If a byte is located that cannot appear at its
+/// position within the number, execute `$on_invalid`.
+///
+/// Exit the collection loop upon locating a byte that is not part of the number.
+#[macro_export]
+macro_rules! bs_collect_float {
+    ($context:expr, $var:expr, $ty:ty, $format:expr, $on_invalid:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
+        loop {
+            let result = $context.collect_float::<$ty>(&$format);
+
+            match result {
+                $crate::FloatCollect::Done(value) | $crate::FloatCollect::Eos(Some(value)) => {
+                    $var = value;
+                },
+                _ => {}
+            }
+
+            match result {
+                $crate::FloatCollect::Done(_) => break,
+                $crate::FloatCollect::Eos(_) => $on_eos,
+                $crate::FloatCollect::Invalid => $on_invalid
+            }
+        }
+    });
+
+    ($context:expr, $var:expr, $ty:ty, $on_invalid:expr, $on_eos:expr) => ({
+        bs_collect_float!($context, $var, $ty, $crate::FloatFormat::STRICT, $on_invalid, $on_eos);
+    });
+}
+
+/// Collect all sequential hex bytes into `$var`, and convert them into an unsigned integer of
+/// type `$acc`. If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating
+/// end-of-stream execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// `$acc` may be any type implementing `UnsignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// Exit the collection loop upon locating a non-hex byte.
+#[macro_export]
+macro_rules! bs_collect_hex {
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr,
+     $ty:ty) => ({
+        use $crate::ByteContext;
+
         bs_collect!($context,
-            if is_digit!($context.byte) {
-                if let Some(value) = ($var as u64).checked_mul(10) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u64) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+            if let Some(digit) = $crate::number::hex_value($context.byte()) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $var as $acc, 16, digit
+                ) {
+                    $var = value as $ty;
+                    $on_byte
                 } else {
                     $on_overflow
                 }
@@ -178,179 +311,130 @@ macro_rules! bs_collect_digits64 {
         );
     });
 
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits64!($context, $var, $on_byte, $on_overflow, $on_eos, u64);
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, $acc, $on_byte, $on_overflow, $on_eos, $acc);
     });
 
-    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_digits64!($context, $var, {}, $on_overflow, $on_eos, u64);
+    ($context:expr, $var:expr, $acc:ty, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, $acc, {}, $on_overflow, $on_eos, $acc);
     });
 }
 
-/// Collect all sequential hex bytes into `$var` (u8), and convert them into an unsigned integer. If
-/// `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// Collect all sequential hex bytes into `$var` (u8), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
 /// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
 ///
 /// Exit the collection loop upon locating a non-hex byte.
 #[macro_export]
 macro_rules! bs_collect_hex8 {
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
-        bs_collect!($context,
-            if $context.byte > b'/' && $context.byte < b':' {
-                // digit
-                if let Some(value) = ($var as u8).checked_mul(16) {
-                    if let Some(value) = value.checked_add($context.byte - b'0') {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > b'@' && $context.byte < b'G' {
-                // A-F
-                if let Some(value) = ($var as u8).checked_mul(16) {
-                    if let Some(value) = value.checked_add($context.byte - b'7') {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > 0x60 && $context.byte < 0x67 {
-                // a-f
-                if let Some(value) = ($var as u8).checked_mul(16) {
-                    if let Some(value) = value.checked_add($context.byte - b'W') {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else {
-                break;
-            },
-            $on_eos
-        );
+        bs_collect_hex!($context, $var, u8, $on_byte, $on_overflow, $on_eos, $ty);
     });
 
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex8!($context, $var, $on_byte, $on_overflow, $on_eos, u8)
+        bs_collect_hex!($context, $var, u8, $on_byte, $on_overflow, $on_eos, u8);
     });
 
     ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex8!($context, $var, {}, $on_overflow, $on_eos, u8)
+        bs_collect_hex!($context, $var, u8, {}, $on_overflow, $on_eos, u8);
     });
 }
 
-/// Collect all sequential hex bytes into `$var` (u16), and convert them into an unsigned integer. If
-/// `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// Collect all sequential hex bytes into `$var` (u16), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
 /// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
 ///
 /// Exit the collection loop upon locating a non-hex byte.
 #[macro_export]
 macro_rules! bs_collect_hex16 {
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
-        bs_collect!($context,
-            if $context.byte > b'/' && $context.byte < b':' {
-                // digit
-                if let Some(value) = ($var as u16).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u16) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > b'@' && $context.byte < b'G' {
-                // A-F
-                if let Some(value) = ($var as u16).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'7') as u16) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > 0x60 && $context.byte < 0x67 {
-                // a-f
-                if let Some(value) = ($var as u16).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'W') as u16) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else {
-                break;
-            },
-            $on_eos
-        );
+        bs_collect_hex!($context, $var, u16, $on_byte, $on_overflow, $on_eos, $ty);
     });
 
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex16!($context, $var, $on_byte, $on_overflow, $on_eos, u16)
+        bs_collect_hex!($context, $var, u16, $on_byte, $on_overflow, $on_eos, u16);
     });
 
     ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex16!($context, $var, {}, $on_overflow, $on_eos, u16)
+        bs_collect_hex!($context, $var, u16, {}, $on_overflow, $on_eos, u16);
     });
 }
 
-/// Collect all sequential hex bytes into `$var` (u32), and convert them into an unsigned integer. If
-/// `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// Collect all sequential hex bytes into `$var` (u32), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
 /// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
 ///
 /// Exit the collection loop upon locating a non-hex byte.
 #[macro_export]
 macro_rules! bs_collect_hex32 {
     ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+        bs_collect_hex!($context, $var, u32, $on_byte, $on_overflow, $on_eos, $ty);
+    });
+
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, u32, $on_byte, $on_overflow, $on_eos, u32);
+    });
+
+    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, u32, {}, $on_overflow, $on_eos, u32);
+    });
+}
+
+/// Collect all sequential hex bytes into `$var` (u64), and convert them into an unsigned integer.
+/// If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
+/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// Exit the collection loop upon locating a non-hex byte.
+#[macro_export]
+macro_rules! bs_collect_hex64 {
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+        bs_collect_hex!($context, $var, u64, $on_byte, $on_overflow, $on_eos, $ty);
+    });
+
+    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, u64, $on_byte, $on_overflow, $on_eos, u64);
+    });
+
+    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_hex!($context, $var, u64, {}, $on_overflow, $on_eos, u64);
+    });
+}
+
+/// Collect an unsigned integer into `$var`, detecting its radix from a `0x`, `0o` or `0b` prefix,
+/// and defaulting to decimal when no prefix is present. The value is converted into an unsigned
+/// integer of type `$acc`. If `$on_byte` is supplied, for each new digit byte execute `$on_byte`.
+/// Upon locating end-of-stream execute `$on_eos`. If an overflow would occur, execute
+/// `$on_overflow`.
+///
+/// `$acc` may be any type implementing `UnsignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in the detected radix.
+#[macro_export]
+macro_rules! bs_collect_prefixed {
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr,
+     $ty:ty) => ({
+        use $crate::ByteContext;
+
+        let mut digits       = 0;
+        let mut leading_zero = false;
+        let mut radix        = 10;
+
         bs_collect!($context,
-            if $context.byte > b'/' && $context.byte < b':' {
-                // digit
-                if let Some(value) = ($var as u32).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u32) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > b'@' && $context.byte < b'G' {
-                // A-F
-                if let Some(value) = ($var as u32).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'7') as u32) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
-                } else {
-                    $on_overflow
-                }
-            } else if $context.byte > 0x60 && $context.byte < 0x67 {
-                // a-f
-                if let Some(value) = ($var as u32).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'W') as u32) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+            if let Some(prefix) = if digits == 1 && leading_zero && radix == 10 {
+                $crate::number::prefix_radix($context.byte())
+            } else {
+                None
+            } {
+                radix = prefix;
+            } else if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $var as $acc, radix, digit
+                ) {
+                    digits       += 1;
+                    leading_zero  = leading_zero || (digits == 1 && digit == 0);
+                    $var          = value as $ty;
+                    $on_byte
                 } else {
                     $on_overflow
                 }
@@ -361,57 +445,93 @@ macro_rules! bs_collect_hex32 {
         );
     });
 
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex32!($context, $var, $on_byte, $on_overflow, $on_eos, u32)
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_prefixed!($context, $var, $acc, $on_byte, $on_overflow, $on_eos, $acc);
     });
 
-    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex32!($context, $var, {}, $on_overflow, $on_eos, u32)
+    ($context:expr, $var:expr, $acc:ty, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_prefixed!($context, $var, $acc, {}, $on_overflow, $on_eos, $acc);
     });
 }
 
-/// Collect all sequential hex bytes into `$var` (u64), and convert them into an unsigned integer. If
-/// `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream
-/// execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+/// Collect all sequential digit bytes in `$radix` into `$var`, and convert them into an unsigned
+/// integer of type `$acc`. `$radix` must be between 2 and 36, and letters represent the values 10
+/// through 35, ignoring case. If `$on_byte` is supplied, for each new byte execute `$on_byte`. Upon
+/// locating end-of-stream execute `$on_eos`. If an overflow would occur, execute `$on_overflow`.
 ///
-/// Exit the collection loop upon locating a non-hex byte.
+/// `$acc` may be any type implementing `UnsignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in `$radix`.
 #[macro_export]
-macro_rules! bs_collect_hex64 {
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr, $ty:ty) => ({
+macro_rules! bs_collect_radix {
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr,
+     $ty:ty) => ({
+        use $crate::ByteContext;
+
+        let radix: u8 = $radix;
+
         bs_collect!($context,
-            if $context.byte > b'/' && $context.byte < b':' {
-                // digit
-                if let Some(value) = ($var as u64).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'0') as u64) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+            if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $var as $acc, radix, digit
+                ) {
+                    $var = value as $ty;
+                    $on_byte
                 } else {
                     $on_overflow
                 }
-            } else if $context.byte > b'@' && $context.byte < b'G' {
-                // A-F
-                if let Some(value) = ($var as u64).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'7') as u64) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+            } else {
+                break;
+            },
+            $on_eos
+        );
+    });
+
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $on_byte:expr, $on_overflow:expr,
+     $on_eos:expr) => ({
+        bs_collect_radix!($context, $var, $acc, $radix, $on_byte, $on_overflow, $on_eos, $acc);
+    });
+
+    ($context:expr, $var:expr, $acc:ty, $radix:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_radix!($context, $var, $acc, $radix, {}, $on_overflow, $on_eos, $acc);
+    });
+}
+
+/// Collect an optional `+` or `-` sign followed by all sequential digit bytes into `$var`, and
+/// convert them into a signed integer of type `$acc`. If `$on_byte` is supplied, for each new digit
+/// byte execute `$on_byte`. Upon locating end-of-stream execute `$on_eos`. If an overflow would
+/// occur, execute `$on_overflow`.
+///
+/// `$acc` may be any type implementing `SignedAccumulator`. If `$ty` is supplied, the collected
+/// value is cast to `$ty` before being stored in `$var`.
+///
+/// Exit the collection loop upon locating a non-digit byte.
+#[macro_export]
+macro_rules! bs_collect_signed {
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr,
+     $ty:ty) => ({
+        use $crate::ByteContext;
+
+        let mut negative = false;
+        let start        = bs_index!($context);
+
+        bs_collect!($context,
+            if bs_index!($context) == start + 1
+            && ($context.byte() == b'-' || $context.byte() == b'+') {
+                negative = $context.byte() == b'-';
+            } else if is_digit!($context.byte()) {
+                if let Some(value) = if negative {
+                    $crate::SignedAccumulator::checked_accumulate_negative(
+                        $var as $acc, 10, $context.byte() - b'0'
+                    )
                 } else {
-                    $on_overflow
-                }
-            } else if $context.byte > 0x60 && $context.byte < 0x67 {
-                // a-f
-                if let Some(value) = ($var as u64).checked_mul(16) {
-                    if let Some(value) = value.checked_add(($context.byte - b'W') as u64) {
-                        $var = value as $ty;
-                        $on_byte
-                    } else {
-                        $on_overflow
-                    }
+                    $crate::SignedAccumulator::checked_accumulate(
+                        $var as $acc, 10, $context.byte() - b'0'
+                    )
+                } {
+                    $var = value as $ty;
+                    $on_byte
                 } else {
                     $on_overflow
                 }
@@ -422,12 +542,12 @@ macro_rules! bs_collect_hex64 {
         );
     });
 
-    ($context:expr, $var:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex64!($context, $var, $on_byte, $on_overflow, $on_eos, u64)
+    ($context:expr, $var:expr, $acc:ty, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_signed!($context, $var, $acc, $on_byte, $on_overflow, $on_eos, $acc);
     });
 
-    ($context:expr, $var:expr, $on_overflow:expr, $on_eos:expr) => ({
-        bs_collect_hex64!($context, $var, {}, $on_overflow, $on_eos, u64)
+    ($context:expr, $var:expr, $acc:ty, $on_overflow:expr, $on_eos:expr) => ({
+        bs_collect_signed!($context, $var, $acc, {}, $on_overflow, $on_eos, $acc);
     });
 }
 
@@ -439,10 +559,12 @@ macro_rules! bs_collect_hex64 {
 #[macro_export]
 macro_rules! bs_collect_length {
     ($context:expr, $length:expr, $on_byte:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
         bs_collect!($context, {
                 $on_byte
 
-                if bs_index!($context) == $context.mark_index + $length {
+                if bs_index!($context) == $context.mark_index() + $length {
                     break;
                 }
             },
@@ -451,7 +573,7 @@ macro_rules! bs_collect_length {
     });
 
     ($context:expr, $length:expr) => ({
-        $context.stream_index += $length;
+        bs_jump!($context, $length);
     });
 }
 
@@ -471,6 +593,20 @@ macro_rules! bs_collect_when {
     });
 }
 
+/// Collect while the bytes are in the `ByteSet` given as `$set`, and return the length of the run
+/// as a `Collect<usize>`.
+///
+/// The byte that ends the run is collected, and `$context.byte` is set to it. `Collect::Eos` is
+/// returned upon locating end-of-stream.
+#[macro_export]
+macro_rules! bs_collect_set {
+    ($context:expr, $set:expr) => ({
+        use $crate::ByteContext;
+
+        $context.collect_set(&$set)
+    });
+}
+
 /// Collect if `$until` yields `false`.
 ///
 /// Exit the collection loop if `$until` yields `true`.
@@ -486,19 +622,35 @@ macro_rules! bs_collect_until {
     });
 }
 
+/// Copy the marked bytes to `$context.write_index`, advance the write index past them, and set
+/// `$context.mark_index` to the current stream index.
+#[macro_export]
+macro_rules! bs_compact {
+    ($context:expr) => ({
+        use $crate::ByteContextMut;
+
+        $context.compact();
+    });
+}
+
+/// Copy the marked bytes, ignoring the very last byte, to `$context.write_index`, advance the write
+/// index past them, and set `$context.mark_index` to the current stream index.
+#[macro_export]
+macro_rules! bs_compact_ignore {
+    ($context:expr) => ({
+        use $crate::ByteContextMut;
+
+        $context.compact_ignore();
+    });
+}
+
 /// Count each occurrence of `$byte` starting at `$context.stream_index` until end-of-stream.
 #[macro_export]
 macro_rules! bs_count {
     ($context:expr, $byte:expr) => ({
-        let mut count = 0;
+        use $crate::ByteContext;
 
-        for n in bs_index!($context)..bs_available!($context) {
-            if $context.stream[n] == $byte {
-                count += 1;
-            }
-        }
-
-        count
+        $context.count($byte)
     });
 }
 
@@ -507,100 +659,220 @@ macro_rules! bs_count {
 #[macro_export]
 macro_rules! bs_count_when {
     ($context:expr, $when:expr) => ({
+        use $crate::ByteContext;
+
         let mut count = 0;
 
-        for n in bs_index!($context)..bs_available!($context) {
-            $context.byte = $context.stream[n];
+        for n in bs_index!($context)..$context.stream().len() {
+            let byte = $context.stream()[n];
+
+            $context.set_byte(byte);
 
             if $when {
                 count += 1;
             }
         }
 
-        $context.byte = $context.stream[bs_index!($context)];
+        if let Some(&byte) = $context.stream().get(bs_index!($context)) {
+            $context.set_byte(byte);
+        }
 
         count
     });
 }
 
+/// Fill `$context` until `$length` bytes are available for reading.
+///
+/// Returns `false` if the stream runs out of bytes first.
+#[macro_export]
+macro_rules! bs_ensure {
+    ($context:expr, $length:expr) => ({
+        use $crate::ByteContext;
+
+        $context.ensure($length)
+    });
+}
+
+/// Fill `$context` with more bytes, and indicate that any were added.
+#[macro_export]
+macro_rules! bs_fill {
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.fill()
+    });
+}
+
 /// Find the first occurrence of `$byte` and return the index relative to `$context.stream_index`.
 ///
 /// `$start` is the starting index relative to `$context.stream_index`.
 #[macro_export]
 macro_rules! bs_find {
     ($context:expr, $start:expr, $byte:expr) => ({
-        let mut index = None;
-
-        if bs_index!($context) + $start < $context.stream.len() {
-            for n in bs_index!($context) + $start..$context.stream.len() {
-                if $byte == $context.stream[n] {
-                    index = Some(n);
-                    break;
-                }
-            }
-        }
+        use $crate::ByteContext;
 
-        index
+        $context.find_from($start, $byte)
     });
 
-    ($context:expr, $byte:expr) => (
-        bs_find!($context, 0, $byte);
-    );
+    ($context:expr, $byte:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find($byte)
+    });
 }
 
-/// Find the first occurrence of `$pattern` and return the index relative to
-/// `$context.stream_index`.
+/// Find the first occurrence of `$byte` and return the index relative to `$context.stream_index`,
+/// filling `$context` until it's found or the stream runs out of bytes.
+#[macro_export]
+macro_rules! bs_find_fill {
+    ($context:expr, $byte:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_fill($byte)
+    });
+}
+
+/// Find the first occurrence of `$a` or `$b` and return the index relative to
+/// `$context.stream_index`.
+///
+/// `$start` is the starting index relative to `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_find2 {
+    ($context:expr, $start:expr, $a:expr, $b:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find2_from($start, $a, $b)
+    });
+
+    ($context:expr, $a:expr, $b:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find2($a, $b)
+    });
+}
+
+/// Find the first occurrence of `$a`, `$b` or `$c` and return the index relative to
+/// `$context.stream_index`.
+///
+/// `$start` is the starting index relative to `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_find3 {
+    ($context:expr, $start:expr, $a:expr, $b:expr, $c:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find3_from($start, $a, $b, $c)
+    });
+
+    ($context:expr, $a:expr, $b:expr, $c:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find3($a, $b, $c)
+    });
+}
+
+/// Find the leftmost occurrence of any pattern of `$finder`, a precompiled `MultiFinder`, and
+/// return the `Match`.
+///
+/// `$start` is the starting index relative to `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_find_multi {
+    ($context:expr, $start:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_multi_from($start, &$finder)
+    });
+
+    ($context:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_multi(&$finder)
+    });
+}
+
+/// Find the first occurrence of `$pattern` and return the index relative to
+/// `$context.stream_index`.
 ///
 /// `$start` is the starting index relative to `$context.stream_index`.
 #[macro_export]
 macro_rules! bs_find_pattern {
     ($context:expr, $start:expr, $pattern:expr) => ({
-        let mut index = None;
-
-        if bs_index!($context) + $start < $context.stream.len() {
-            'outer:
-            for s in bs_index!($context) + $start..$context.stream.len() {
-                for (p, byte) in $pattern.iter().enumerate() {
-                    if $context.stream.len() <= s + p || *byte != $context.stream[s + p] {
-                        break;
-                    } else if $pattern.len() == p + 1 {
-                        index = Some(s);
-
-                        break 'outer;
-                    }
-                }
-            }
-        }
+        use $crate::ByteContext;
 
-        index
+        $context.find_pattern_from($start, &$pattern[..])
     });
 
-    ($context:expr, $pattern:expr) => (
-        bs_find_pattern!($context, 0, $pattern);
-    );
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_pattern(&$pattern[..])
+    });
+}
+
+/// Find the first occurrence of `$pattern`, ignoring ASCII case, and return the index relative to
+/// `$context.stream_index`.
+///
+/// `$start` is the starting index relative to `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_find_pattern_ignore_case {
+    ($context:expr, $start:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_pattern_ignore_case_from($start, &$pattern[..])
+    });
+
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_pattern_ignore_case(&$pattern[..])
+    });
+}
+
+/// Find the first occurrence of the pattern of `$finder`, a precompiled `Finder`, and return the
+/// index relative to `$context.stream_index`.
+///
+/// `$start` is the starting index relative to `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_find_with {
+    ($context:expr, $start:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_with_from($start, &$finder)
+    });
+
+    ($context:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.find_with(&$finder)
+    });
 }
 
 /// Indicates that a specified amount of bytes are available for reading.
 #[macro_export]
 macro_rules! bs_has_bytes {
-    ($context:expr, $length:expr) => (
-        bs_index!($context) + $length <= $context.stream.len()
-    );
+    ($context:expr, $length:expr) => ({
+        use $crate::ByteContext;
+
+        $context.has_bytes($length)
+    });
 }
 
 /// Retrieve the current stream index.
 #[macro_export]
 macro_rules! bs_index {
-    ($context:expr) => (
-        $context.stream_index
-    );
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.index()
+    });
 }
 
 /// Indicates that we're at the end of the stream.
 #[macro_export]
 macro_rules! bs_is_eos {
     ($context:expr) => ({
-        bs_index!($context) == $context.stream.len()
+        use $crate::ByteContext;
+
+        $context.is_eos()
     });
 }
 
@@ -610,7 +882,9 @@ macro_rules! bs_is_eos {
 #[macro_export]
 macro_rules! bs_jump {
     ($context:expr, $length:expr) => ({
-        $context.stream_index += $length;
+        use $crate::ByteContext;
+
+        $context.jump($length);
     });
 }
 
@@ -618,23 +892,140 @@ macro_rules! bs_jump {
 #[macro_export]
 macro_rules! bs_mark {
     ($context:expr) => ({
-        $context.mark_index = bs_index!($context);
+        use $crate::ByteContext;
+
+        $context.mark();
     });
 
     ($context:expr, $index:expr) => ({
-        $context.mark_index = $index;
+        use $crate::ByteContext;
+
+        $context.set_mark_index($index);
+    });
+}
+
+/// Run a resumable state machine over `$context`, where `$state` is a mutable place holding the
+/// current state.
+///
+/// The arms are matched against `$state` in a loop, so that an arm that moves to another state with
+/// `bs_transition!()` is followed by the arm for that state. Before each arm, `$context` is filled
+/// upon locating end-of-stream, so that an arm sees any bytes that can be read. Arms run even when
+/// no bytes are available, so that a state that consumes no bytes can finish, and an arm that needs
+/// more bytes returns with `bs_need_more!()` itself, typically from a collector's `$on_eos`.
+///
+/// The machine finishes only by returning from the enclosing function, which is typically a
+/// `ParseResult`. Since `$state` persists, the next call resumes in the state where more bytes were
+/// needed.
+#[macro_export]
+macro_rules! bs_machine {
+    ($context:expr, $state:expr, { $($pattern:pat => $arm:expr),+ $(,)* }) => ({
+        loop {
+            if bs_is_eos!($context) {
+                bs_fill!($context);
+            }
+
+            match $state {
+                $($pattern => $arm),+
+            }
+        }
     });
 }
 
+/// Match the remaining stream against a list of keywords, advancing `$context.stream_index` past
+/// the matched keyword and setting `$context.byte` to its last byte.
+///
+/// The keywords are built into a `Keywords` table at compile time, which is switched on the leading
+/// byte and the remaining length. The longest matching keyword is matched, so a keyword that's a
+/// prefix of another never shadows it. When no keyword matches, the stream is left untouched.
+///
+/// With a list of keywords, the index of the matched keyword is returned:
+///
+/// ```ignore
+/// bs_match!(context, [b"GET", b"POST", b"PUT"])
+/// ```
+///
+/// With a list of arms, the arms expand into a `match` on the index of the matched keyword, so that
+/// the matched arm is evaluated, or the `_` arm when no keyword matches. The `_` arm may appear
+/// anywhere, and may be left out when the arms evaluate to `()`:
+///
+/// ```ignore
+/// bs_match!(context, {
+///     b"GET"  => Method::Get,
+///     b"POST" => Method::Post,
+///     _       => Method::Unknown
+/// })
+/// ```
+#[macro_export]
+macro_rules! bs_match {
+    (@arms $context:expr, [$($keyword:expr),*] [$($index:expr => $arm:expr),*] [$($default:expr)*]
+     ($($count:tt)*)) => ({
+        match bs_match!($context, [$($keyword),*]) {
+            $(Some(index) if index == $index => $arm,)*
+            _ => { $($default)* }
+        }
+    });
+
+    (@arms $context:expr, [$($keyword:expr),*] [$($index:expr => $arm:expr),*] []
+     ($($count:tt)*) _ => $default:expr, $($rest:tt)*) => (
+        bs_match!(@arms $context, [$($keyword),*] [$($index => $arm),*] [$default]
+                  ($($count)*) $($rest)*)
+    );
+
+    (@arms $context:expr, [$($keyword:expr),*] [$($index:expr => $arm:expr),*] []
+     ($($count:tt)*) _ => $default:expr) => (
+        bs_match!(@arms $context, [$($keyword),*] [$($index => $arm),*] [$default] ($($count)*))
+    );
+
+    (@arms $context:expr, [$($keyword:expr),*] [$($index:expr => $arm:expr),*] [$($default:expr)*]
+     ($($count:tt)*) $next_keyword:expr => $next_arm:expr, $($rest:tt)*) => (
+        bs_match!(@arms $context, [$($keyword,)* $next_keyword]
+                  [$($index => $arm,)* ($($count)*) => $next_arm] [$($default)*]
+                  ($($count)* + 1) $($rest)*)
+    );
+
+    (@arms $context:expr, [$($keyword:expr),*] [$($index:expr => $arm:expr),*] [$($default:expr)*]
+     ($($count:tt)*) $next_keyword:expr => $next_arm:expr) => (
+        bs_match!(@arms $context, [$($keyword,)* $next_keyword]
+                  [$($index => $arm,)* ($($count)*) => $next_arm] [$($default)*] ($($count)*))
+    );
+
+    ($context:expr, [$($keyword:expr),+ $(,)*]) => ({
+        use $crate::ByteContext;
+
+        $context.match_keyword(&const { $crate::Keywords::new([$($keyword as &'static [u8]),+]) })
+    });
+
+    ($context:expr, { $($arms:tt)* }) => (
+        bs_match!(@arms $context, [] [] [] (0) $($arms)*)
+    );
+}
+
+/// Return `ParseResult::NeedMore` from the enclosing function, so that parsing resumes in the
+/// current state with more bytes.
+#[macro_export]
+macro_rules! bs_need_more {
+    () => (
+        return $crate::ParseResult::NeedMore
+    );
+}
+
 /// Advance `$context.stream_index` one byte and set `$context.byte` to the new byte.
 #[macro_export]
 macro_rules! bs_next {
     ($context:expr) => ({
-        $context.byte = unsafe {
-            *$context.stream.get_unchecked(bs_index!($context))
-        };
+        use $crate::ByteContext;
 
-        $context.stream_index += 1;
+        $context.next();
+    });
+}
+
+/// Advance the stream by one byte, returning the byte, or `None` at the end of the stream.
+#[macro_export]
+macro_rules! bs_try_next {
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.try_next()
     });
 }
 
@@ -643,24 +1034,287 @@ macro_rules! bs_next {
 /// This macro assumes that `$length` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_peek {
-    ($context:expr, $length:expr) => (
-        &$context.stream[bs_index!($context)..bs_index!($context) + $length]
-    );
+    ($context:expr, $length:expr) => ({
+        use $crate::ByteContext;
+
+        $context.peek($length)
+    });
 }
 
 /// Retrieve the remaining available bytes.
 #[macro_export]
 macro_rules! bs_remaining {
-    ($context:expr) => (
-        &$context.stream[bs_index!($context)..]
-    );
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.remaining()
+    });
 }
 
 /// Replay the most recent byte, but do not change the current `$context.byte`.
 #[macro_export]
 macro_rules! bs_replay {
     ($context:expr) => ({
-        bs_rewind!($context, 1);
+        use $crate::ByteContext;
+
+        $context.replay();
+    });
+}
+
+/// Resume collecting all sequential digit bytes in `$radix` into `$state`, a `NumberState` that
+/// carries the collection progress across streams, enforcing the `DigitPolicy` given as `$policy`.
+/// `$radix` must be between 2 and 36. If `$on_byte` is supplied, for each new digit byte execute
+/// `$on_byte`. Upon locating end-of-stream execute `$on_eos`. If an overflow would occur, execute
+/// `$on_overflow`. If a digit would violate `$policy`, or if collection would finish with fewer
+/// digits than `$policy` requires, execute `$on_violation`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in `$radix`.
+#[macro_export]
+macro_rules! bs_resume_bounded {
+    ($context:expr, $state:expr, $radix:expr, $policy:expr, $on_byte:expr, $on_overflow:expr,
+     $on_violation:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
+        let policy: $crate::DigitPolicy = $policy;
+        let radix: u8 = $radix;
+
+        bs_collect!($context,
+            if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                let leading_zero = if $state.digits == 0 {
+                    digit == 0
+                } else {
+                    $state.leading_zero
+                };
+
+                if !policy.allows($state.digits + 1, leading_zero) {
+                    $on_violation
+                } else if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $state.value, radix, digit
+                ) {
+                    $state.digits       += 1;
+                    $state.leading_zero  = leading_zero;
+                    $state.value         = value;
+                    $on_byte
+                } else {
+                    $on_overflow
+                }
+            } else if policy.is_satisfied($state.digits) {
+                break;
+            } else {
+                $on_violation
+            },
+            $on_eos
+        );
+    });
+
+    ($context:expr, $state:expr, $radix:expr, $policy:expr, $on_overflow:expr,
+     $on_violation:expr, $on_eos:expr) => ({
+        bs_resume_bounded!($context, $state, $radix, $policy, {}, $on_overflow, $on_violation,
+                           $on_eos);
+    });
+}
+
+/// Resume collecting an unsigned integer into `$state`, a `NumberState` that carries the collection
+/// progress across streams, detecting its radix from a `0x`, `0o` or `0b` prefix, and defaulting
+/// to decimal when no prefix is present. If `$on_byte` is supplied, for each new digit byte execute
+/// `$on_byte`. Upon locating end-of-stream execute `$on_eos`. If an overflow would occur, execute
+/// `$on_overflow`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in the detected radix.
+#[macro_export]
+macro_rules! bs_resume_prefixed {
+    ($context:expr, $state:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
+        bs_collect!($context, {
+            let radix = if $state.radix == 0 { 10 } else { $state.radix };
+
+            if let Some(prefix) = if $state.digits == 1 && $state.leading_zero
+                                  && $state.radix == 0 {
+                $crate::number::prefix_radix($context.byte())
+            } else {
+                None
+            } {
+                $state.radix = prefix;
+            } else if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $state.value, radix, digit
+                ) {
+                    $state.leading_zero  = $state.leading_zero
+                                        || ($state.digits == 0 && digit == 0);
+                    $state.digits       += 1;
+                    $state.value         = value;
+                    $on_byte
+                } else {
+                    $on_overflow
+                }
+            } else {
+                break;
+            }
+        }, $on_eos);
+    });
+
+    ($context:expr, $state:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_resume_prefixed!($context, $state, {}, $on_overflow, $on_eos);
+    });
+}
+
+/// Resume collecting all sequential digit bytes in `$radix` into `$state`, a `NumberState` that
+/// carries the collection progress across streams. `$radix` must be between 2 and 36. If `$on_byte`
+/// is supplied, for each new byte execute `$on_byte`. Upon locating end-of-stream execute
+/// `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// Exit the collection loop upon locating a byte that is not a digit in `$radix`.
+#[macro_export]
+macro_rules! bs_resume_radix {
+    ($context:expr, $state:expr, $radix:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
+        let radix: u8 = $radix;
+
+        bs_collect!($context,
+            if let Some(digit) = $crate::number::radix_value($context.byte(), radix) {
+                if let Some(value) = $crate::UnsignedAccumulator::checked_accumulate(
+                    $state.value, radix, digit
+                ) {
+                    $state.digits += 1;
+                    $state.value   = value;
+                    $on_byte
+                } else {
+                    $on_overflow
+                }
+            } else {
+                break;
+            },
+            $on_eos
+        );
+    });
+
+    ($context:expr, $state:expr, $radix:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_resume_radix!($context, $state, $radix, {}, $on_overflow, $on_eos);
+    });
+}
+
+/// Resume collecting an optional `+` or `-` sign followed by all sequential digit bytes into
+/// `$state`, a `NumberState` that carries the collection progress across streams. If `$on_byte` is
+/// supplied, for each new digit byte execute `$on_byte`. Upon locating end-of-stream execute
+/// `$on_eos`. If an overflow would occur, execute `$on_overflow`.
+///
+/// Exit the collection loop upon locating a non-digit byte.
+#[macro_export]
+macro_rules! bs_resume_signed {
+    ($context:expr, $state:expr, $on_byte:expr, $on_overflow:expr, $on_eos:expr) => ({
+        use $crate::ByteContext;
+
+        bs_collect!($context,
+            if !$state.is_started() && ($context.byte() == b'-' || $context.byte() == b'+') {
+                $state.negative = $context.byte() == b'-';
+                $state.signed   = true;
+            } else if is_digit!($context.byte()) {
+                if let Some(value) = if $state.negative {
+                    $crate::SignedAccumulator::checked_accumulate_negative(
+                        $state.value, 10, $context.byte() - b'0'
+                    )
+                } else {
+                    $crate::SignedAccumulator::checked_accumulate(
+                        $state.value, 10, $context.byte() - b'0'
+                    )
+                } {
+                    $state.digits += 1;
+                    $state.value   = value;
+                    $on_byte
+                } else {
+                    $on_overflow
+                }
+            } else {
+                break;
+            },
+            $on_eos
+        );
+    });
+
+    ($context:expr, $state:expr, $on_overflow:expr, $on_eos:expr) => ({
+        bs_resume_signed!($context, $state, {}, $on_overflow, $on_eos);
+    });
+}
+
+/// Find the last occurrence of `$byte`, searching backwards from the end of `$context.stream`, and
+/// return the index relative to `$context.stream_index`.
+///
+/// `$start` is the index relative to `$context.stream_index` where the search ends.
+#[macro_export]
+macro_rules! bs_rfind {
+    ($context:expr, $start:expr, $byte:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_from($start, $byte)
+    });
+
+    ($context:expr, $byte:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind($byte)
+    });
+}
+
+/// Find the last occurrence of `$byte` before `$context.stream_index`, searching backwards through
+/// the bytes that have already been read, and return its index.
+#[macro_export]
+macro_rules! bs_rfind_before {
+    ($context:expr, $byte:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_before($byte)
+    });
+}
+
+/// Find the last occurrence of `$pattern`, searching backwards from the end of `$context.stream`,
+/// and return the index relative to `$context.stream_index`.
+///
+/// `$start` is the index relative to `$context.stream_index` where the search ends.
+#[macro_export]
+macro_rules! bs_rfind_pattern {
+    ($context:expr, $start:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_pattern_from($start, &$pattern[..])
+    });
+
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_pattern(&$pattern[..])
+    });
+}
+
+/// Find the last occurrence of `$pattern` that ends at or before `$context.stream_index`, searching
+/// backwards through the bytes that have already been read, and return its index.
+#[macro_export]
+macro_rules! bs_rfind_pattern_before {
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_pattern_before(&$pattern[..])
+    });
+}
+
+/// Find the last occurrence of the pattern of `$finder`, a precompiled `FinderRev`, searching
+/// backwards from the end of `$context.stream`, and return the index relative to
+/// `$context.stream_index`.
+///
+/// `$start` is the index relative to `$context.stream_index` where the search ends.
+#[macro_export]
+macro_rules! bs_rfind_with {
+    ($context:expr, $start:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_with_from($start, &$finder)
+    });
+
+    ($context:expr, $finder:expr) => ({
+        use $crate::ByteContext;
+
+        $context.rfind_with(&$finder)
     });
 }
 
@@ -669,7 +1323,9 @@ macro_rules! bs_replay {
 #[macro_export]
 macro_rules! bs_rewind {
     ($context:expr, $length:expr) => ({
-        $context.stream_index -= $length;
+        use $crate::ByteContext;
+
+        $context.rewind($length);
     });
 }
 
@@ -677,31 +1333,52 @@ macro_rules! bs_rewind {
 #[macro_export]
 macro_rules! bs_rewind_to {
     ($context:expr, $index:expr) => ({
-        $context.stream_index = $index;
+        use $crate::ByteContext;
+
+        $context.rewind_to($index);
+    });
+}
+
+/// Skip the run of bytes in the `ByteSet` given as `$set`, stopping before the first byte that
+/// isn't, and return the length of the run.
+///
+/// `$context.byte` is set to the last byte of the run, if any.
+#[macro_export]
+macro_rules! bs_skip_set {
+    ($context:expr, $set:expr) => ({
+        use $crate::ByteContext;
+
+        $context.skip_set(&$set)
     });
 }
 
 /// Retrieve the slice of marked bytes.
 #[macro_export]
 macro_rules! bs_slice {
-    ($context:expr) => (
-        &$context.stream[$context.mark_index..bs_index!($context)];
-    );
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.slice()
+    });
 }
 
 /// Retrieve the slice of marked bytes ignoring the very last byte.
 #[macro_export]
 macro_rules! bs_slice_ignore {
-    ($context:expr) => (
-        &$context.stream[$context.mark_index..bs_index!($context) - 1];
-    );
+    ($context:expr) => ({
+        use $crate::ByteContext;
+
+        $context.slice_ignore()
+    });
 }
 
 /// Retrieve the length of marked bytes.
 #[macro_export]
 macro_rules! bs_slice_length {
     ($context:expr) => ({
-        bs_index!($context) - $context.mark_index
+        use $crate::ByteContext;
+
+        $context.slice_length()
     });
 }
 
@@ -711,7 +1388,64 @@ macro_rules! bs_slice_length {
 #[macro_export]
 macro_rules! bs_starts_with {
     ($context:expr, $pattern:expr) => ({
-        &$context.stream[$context.stream_index..$context.stream_index+$pattern.len()] == $pattern
+        use $crate::ByteContext;
+
+        $context.starts_with(&$pattern[..])
+    });
+}
+
+/// Determine if the remaining stream starts with `$pattern`, ignoring ASCII case.
+///
+/// This macro assumes that `$pattern.len()` bytes are available for reading.
+#[macro_export]
+macro_rules! bs_starts_with_ignore_case {
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.starts_with_ignore_case(&$pattern[..])
+    });
+}
+
+/// Determine if the remaining stream starts with the fixed-length `$pattern`, comparing a word at a
+/// time.
+///
+/// `$pattern` must be a `&[u8; N]`, such as a byte string literal.
+///
+/// This macro assumes that `$pattern.len()` bytes are available for reading.
+#[macro_export]
+macro_rules! bs_starts_with_array {
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        let index   = $context.stream_index();
+        let pattern = $pattern;
+        let stream  = $context.stream();
+
+        unsafe { $crate::search::starts_with_unchecked(stream, index, pattern) }
+    });
+}
+
+/// Determine if the remaining stream starts with `$pattern`, comparing only the first `$length`
+/// bytes a word at a time.
+///
+/// This macro assumes that `$length` bytes are available for reading, and that `$pattern` is at
+/// least `$length` bytes long.
+#[macro_export]
+macro_rules! bs_starts_with_n {
+    ($context:expr, $length:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        const LENGTH: usize = $length;
+
+        let index   = $context.stream_index();
+        let pattern = &$pattern[..];
+        let stream  = $context.stream();
+
+        unsafe {
+            let pattern = $crate::search::prefix_unchecked::<LENGTH>(pattern);
+
+            $crate::search::starts_with_unchecked(stream, index, pattern)
+        }
     });
 }
 
@@ -720,9 +1454,9 @@ macro_rules! bs_starts_with {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with1 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index) == $pattern.get_unchecked(0)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 1, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 2 bytes.
@@ -730,11 +1464,9 @@ macro_rules! bs_starts_with1 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with2 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)
-        == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 2, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 3 bytes.
@@ -742,11 +1474,9 @@ macro_rules! bs_starts_with2 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with3 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 3, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 4 bytes.
@@ -754,12 +1484,9 @@ macro_rules! bs_starts_with3 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with4 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 4, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 5 bytes.
@@ -767,13 +1494,9 @@ macro_rules! bs_starts_with4 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with5 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 5, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 6 bytes.
@@ -781,14 +1504,9 @@ macro_rules! bs_starts_with5 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with6 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5) == $pattern.get_unchecked(5)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 6, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 7 bytes.
@@ -796,15 +1514,9 @@ macro_rules! bs_starts_with6 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with7 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5) == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6) == $pattern.get_unchecked(6)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 7, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 8 bytes.
@@ -812,16 +1524,9 @@ macro_rules! bs_starts_with7 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with8 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5) == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6) == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7) == $pattern.get_unchecked(7)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 8, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 9 bytes.
@@ -829,17 +1534,9 @@ macro_rules! bs_starts_with8 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with9 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5) == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6) == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7) == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8) == $pattern.get_unchecked(8)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 9, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 10 bytes.
@@ -847,18 +1544,9 @@ macro_rules! bs_starts_with9 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with10 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)     == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1) == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2) == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3) == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4) == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5) == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6) == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7) == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8) == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9) == $pattern.get_unchecked(9)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 10, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 11 bytes.
@@ -866,19 +1554,9 @@ macro_rules! bs_starts_with10 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with11 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 11, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 12 bytes.
@@ -886,20 +1564,9 @@ macro_rules! bs_starts_with11 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with12 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 12, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 13 bytes.
@@ -907,21 +1574,9 @@ macro_rules! bs_starts_with12 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with13 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 13, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 14 bytes.
@@ -929,22 +1584,9 @@ macro_rules! bs_starts_with13 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with14 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 14, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 15 bytes.
@@ -952,23 +1594,9 @@ macro_rules! bs_starts_with14 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with15 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 15, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 16 bytes.
@@ -976,24 +1604,9 @@ macro_rules! bs_starts_with15 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with16 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 16, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 17 bytes.
@@ -1001,25 +1614,9 @@ macro_rules! bs_starts_with16 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with17 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 17, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 18 bytes.
@@ -1027,26 +1624,9 @@ macro_rules! bs_starts_with17 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with18 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 18, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 19 bytes.
@@ -1054,27 +1634,9 @@ macro_rules! bs_starts_with18 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with19 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 19, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 20 bytes.
@@ -1082,28 +1644,9 @@ macro_rules! bs_starts_with19 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with20 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 20, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 21 bytes.
@@ -1111,29 +1654,9 @@ macro_rules! bs_starts_with20 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with21 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-        && $context.stream.get_unchecked($context.stream_index + 20) == $pattern.get_unchecked(20)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 21, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 22 bytes.
@@ -1141,30 +1664,9 @@ macro_rules! bs_starts_with21 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with22 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-        && $context.stream.get_unchecked($context.stream_index + 20) == $pattern.get_unchecked(20)
-        && $context.stream.get_unchecked($context.stream_index + 21) == $pattern.get_unchecked(21)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 22, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 23 bytes.
@@ -1172,31 +1674,9 @@ macro_rules! bs_starts_with22 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with23 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-        && $context.stream.get_unchecked($context.stream_index + 20) == $pattern.get_unchecked(20)
-        && $context.stream.get_unchecked($context.stream_index + 21) == $pattern.get_unchecked(21)
-        && $context.stream.get_unchecked($context.stream_index + 22) == $pattern.get_unchecked(22)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 23, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 24 bytes.
@@ -1204,32 +1684,9 @@ macro_rules! bs_starts_with23 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with24 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-        && $context.stream.get_unchecked($context.stream_index + 20) == $pattern.get_unchecked(20)
-        && $context.stream.get_unchecked($context.stream_index + 21) == $pattern.get_unchecked(21)
-        && $context.stream.get_unchecked($context.stream_index + 22) == $pattern.get_unchecked(22)
-        && $context.stream.get_unchecked($context.stream_index + 23) == $pattern.get_unchecked(23)
-    });
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 24, $pattern)
+    );
 }
 
 /// Determine if the remaining stream starts with `$pattern`, comparing only the first 25 bytes.
@@ -1237,32 +1694,100 @@ macro_rules! bs_starts_with24 {
 /// This macro assumes that `$pattern.len()` bytes are available for reading.
 #[macro_export]
 macro_rules! bs_starts_with25 {
-    ($context:expr, $pattern:expr) => (unsafe {
-           $context.stream.get_unchecked($context.stream_index)      == $pattern.get_unchecked(0)
-        && $context.stream.get_unchecked($context.stream_index + 1)  == $pattern.get_unchecked(1)
-        && $context.stream.get_unchecked($context.stream_index + 2)  == $pattern.get_unchecked(2)
-        && $context.stream.get_unchecked($context.stream_index + 3)  == $pattern.get_unchecked(3)
-        && $context.stream.get_unchecked($context.stream_index + 4)  == $pattern.get_unchecked(4)
-        && $context.stream.get_unchecked($context.stream_index + 5)  == $pattern.get_unchecked(5)
-        && $context.stream.get_unchecked($context.stream_index + 6)  == $pattern.get_unchecked(6)
-        && $context.stream.get_unchecked($context.stream_index + 7)  == $pattern.get_unchecked(7)
-        && $context.stream.get_unchecked($context.stream_index + 8)  == $pattern.get_unchecked(8)
-        && $context.stream.get_unchecked($context.stream_index + 9)  == $pattern.get_unchecked(9)
-        && $context.stream.get_unchecked($context.stream_index + 10) == $pattern.get_unchecked(10)
-        && $context.stream.get_unchecked($context.stream_index + 11) == $pattern.get_unchecked(11)
-        && $context.stream.get_unchecked($context.stream_index + 12) == $pattern.get_unchecked(12)
-        && $context.stream.get_unchecked($context.stream_index + 13) == $pattern.get_unchecked(13)
-        && $context.stream.get_unchecked($context.stream_index + 14) == $pattern.get_unchecked(14)
-        && $context.stream.get_unchecked($context.stream_index + 15) == $pattern.get_unchecked(15)
-        && $context.stream.get_unchecked($context.stream_index + 16) == $pattern.get_unchecked(16)
-        && $context.stream.get_unchecked($context.stream_index + 17) == $pattern.get_unchecked(17)
-        && $context.stream.get_unchecked($context.stream_index + 18) == $pattern.get_unchecked(18)
-        && $context.stream.get_unchecked($context.stream_index + 19) == $pattern.get_unchecked(19)
-        && $context.stream.get_unchecked($context.stream_index + 20) == $pattern.get_unchecked(20)
-        && $context.stream.get_unchecked($context.stream_index + 21) == $pattern.get_unchecked(21)
-        && $context.stream.get_unchecked($context.stream_index + 22) == $pattern.get_unchecked(22)
-        && $context.stream.get_unchecked($context.stream_index + 23) == $pattern.get_unchecked(23)
-        && $context.stream.get_unchecked($context.stream_index + 24) == $pattern.get_unchecked(24)
+    ($context:expr, $pattern:expr) => (
+        bs_starts_with_n!($context, 25, $pattern)
+    );
+}
+
+/// Move `$state` to `$next`, so that a state machine run by `bs_machine!()` continues in the arm
+/// for `$next`.
+///
+/// If `$context` is supplied, `$context.mark_index` is also set to the current stream index, which
+/// marks the start of the next state's bytes.
+#[macro_export]
+macro_rules! bs_transition {
+    ($context:expr, $state:expr, $next:expr) => ({
+        bs_mark!($context);
+
+        $state = $next;
+    });
+
+    ($state:expr, $next:expr) => ({
+        $state = $next;
+    });
+}
+
+/// Determine if the remaining stream starts with `$pattern`.
+///
+/// Unlike `bs_starts_with!()`, this returns `false` when fewer than `$pattern.len()` bytes are
+/// available.
+#[macro_export]
+macro_rules! bs_try_starts_with {
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        let pattern = &$pattern[..];
+
+        match $context.stream().get(bs_index!($context)..) {
+            Some(bytes) => bytes.starts_with(pattern),
+            None        => false
+        }
+    });
+}
+
+/// Determine if the remaining stream starts with the fixed-length `$pattern`, comparing a word at a
+/// time.
+///
+/// `$pattern` must be a `&[u8; N]`, such as a byte string literal. Unlike
+/// `bs_starts_with_array!()`, this returns `false` when fewer than `$pattern.len()` bytes are
+/// available.
+#[macro_export]
+macro_rules! bs_try_starts_with_array {
+    ($context:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        $context.try_starts_with($pattern)
+    });
+}
+
+/// Determine if the remaining stream starts with `$pattern`, comparing only the first `$length`
+/// bytes a word at a time.
+///
+/// Unlike `bs_starts_with_n!()`, this returns `false` when fewer than `$length` bytes are
+/// available, or when `$pattern` is shorter than `$length` bytes.
+#[macro_export]
+macro_rules! bs_try_starts_with_n {
+    ($context:expr, $length:expr, $pattern:expr) => ({
+        use $crate::ByteContext;
+
+        const LENGTH: usize = $length;
+
+        match $crate::search::prefix::<LENGTH>(&$pattern[..]) {
+            Some(pattern) => $context.try_starts_with(pattern),
+            None          => false
+        }
+    });
+}
+
+/// Write `$byte` at `$context.write_index`, and advance the write index one byte.
+///
+/// Panics if the write index would pass `$context.stream_index`.
+#[macro_export]
+macro_rules! bs_write_byte {
+    ($context:expr, $byte:expr) => ({
+        use $crate::ByteContextMut;
+
+        $context.write_byte($byte);
+    });
+}
+
+/// Retrieve the written bytes.
+#[macro_export]
+macro_rules! bs_written {
+    ($context:expr) => ({
+        use $crate::ByteContextMut;
+
+        $context.written()
     });
 }
 
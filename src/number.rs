@@ -0,0 +1,449 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Numeric accumulation.
+
+use std::convert::TryFrom;
+use std::str::{ self, FromStr };
+
+/// Unsigned integer type that digits can be collected into.
+pub trait UnsignedAccumulator: Copy {
+    /// The value `0`.
+    const ZERO: Self;
+
+    /// Multiply the value by `radix` and add `digit`.
+    ///
+    /// Returns `None` if an overflow would occur.
+    fn checked_accumulate(self, radix: u8, digit: u8) -> Option<Self>;
+
+    /// Multiply the value by `100_000_000` and add `digits`, the value of eight decimal digits.
+    ///
+    /// Returns `None` if an overflow would occur.
+    fn checked_accumulate_eight(self, digits: u32) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned_accumulator {
+    ($($ty:ty),*) => ($(
+        impl UnsignedAccumulator for $ty {
+            const ZERO: $ty = 0;
+
+            #[inline]
+            fn checked_accumulate(self, radix: u8, digit: u8) -> Option<$ty> {
+                self.checked_mul(radix as $ty)?.checked_add(digit as $ty)
+            }
+
+            #[inline]
+            fn checked_accumulate_eight(self, digits: u32) -> Option<$ty> {
+                self.checked_mul(<$ty>::try_from(100_000_000u32).ok()?)?
+                    .checked_add(<$ty>::try_from(digits).ok()?)
+            }
+        }
+    )*);
+}
+
+impl_unsigned_accumulator!(u8, u16, u32, u64, u128, usize);
+
+/// Signed integer type that digits can be collected into.
+pub trait SignedAccumulator: Copy {
+    /// The value `0`.
+    const ZERO: Self;
+
+    /// Multiply the value by `radix` and add `digit`.
+    ///
+    /// Returns `None` if an overflow would occur.
+    fn checked_accumulate(self, radix: u8, digit: u8) -> Option<Self>;
+
+    /// Multiply the value by `radix` and subtract `digit`.
+    ///
+    /// Negative values are accumulated below zero so that the minimum value can be represented.
+    ///
+    /// Returns `None` if an overflow would occur.
+    fn checked_accumulate_negative(self, radix: u8, digit: u8) -> Option<Self>;
+}
+
+macro_rules! impl_signed_accumulator {
+    ($($ty:ty),*) => ($(
+        impl SignedAccumulator for $ty {
+            const ZERO: $ty = 0;
+
+            #[inline]
+            fn checked_accumulate(self, radix: u8, digit: u8) -> Option<$ty> {
+                self.checked_mul(radix as $ty)?.checked_add(digit as $ty)
+            }
+
+            #[inline]
+            fn checked_accumulate_negative(self, radix: u8, digit: u8) -> Option<$ty> {
+                self.checked_mul(radix as $ty)?.checked_sub(digit as $ty)
+            }
+        }
+    )*);
+}
+
+impl_signed_accumulator!(i8, i16, i32, i64, i128, isize);
+
+/// Convert a hex byte into its numeric value.
+///
+/// Returns `None` if `byte` is not a hex character.
+#[inline]
+pub fn hex_value(byte: u8) -> Option<u8> {
+    if byte > b'/' && byte < b':' {
+        Some(byte - b'0')
+    } else if byte > b'@' && byte < b'G' {
+        Some(byte - b'7')
+    } else if byte > 0x60 && byte < 0x67 {
+        Some(byte - b'W')
+    } else {
+        None
+    }
+}
+
+/// Numeric collection state that can be carried across stream boundaries.
+///
+/// When a resumable collector locates end-of-stream, the state records the progress of the number
+/// so that collection can continue with the next stream.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NumberState<T> {
+    /// Amount of digits collected.
+    pub digits: usize,
+
+    /// Indicates that the first digit collected was `0`.
+    pub leading_zero: bool,
+
+    /// Indicates that a `-` sign was collected.
+    pub negative: bool,
+
+    /// Radix detected from a `0x`, `0o` or `0b` prefix, or `0` if no prefix was collected.
+    pub radix: u8,
+
+    /// Indicates that a `+` or `-` sign was collected.
+    pub signed: bool,
+
+    /// Value collected so far.
+    pub value: T
+}
+
+impl<T> NumberState<T> {
+    /// Create a new `NumberState` that starts collecting from `value`.
+    pub fn new(value: T) -> NumberState<T> {
+        NumberState{
+            digits:       0,
+            leading_zero: false,
+            negative:     false,
+            radix:        0,
+            signed:       false,
+            value
+        }
+    }
+
+    /// Indicates that a number is in progress.
+    #[inline]
+    pub fn is_started(&self) -> bool {
+        self.digits > 0 || self.signed
+    }
+}
+
+/// Convert eight decimal digit bytes into their numeric value, processing all bytes at once.
+///
+/// Returns `None` if any of the first eight bytes is not a digit.
+///
+/// This function assumes that `bytes` contains at least eight bytes.
+#[inline]
+pub fn eight_digits(bytes: &[u8]) -> Option<u32> {
+    let mut word = [0; 8];
+
+    word.copy_from_slice(&bytes[..8]);
+
+    let chunk = u64::from_le_bytes(word);
+
+    // each digit has a high nibble of 3, and does not carry into the high nibble when 6 is added
+    if (chunk & 0xF0F0_F0F0_F0F0_F0F0)
+     | ((chunk.wrapping_add(0x0606_0606_0606_0606) & 0xF0F0_F0F0_F0F0_F0F0) >> 4)
+    != 0x3333_3333_3333_3333 {
+        return None;
+    }
+
+    // the first digit is the lowest byte, so combine adjacent digits, then pairs, then quads
+    let mut value = chunk - 0x3030_3030_3030_3030;
+
+    value = (value * 10 + (value >> 8)) & 0x00FF_00FF_00FF_00FF;
+    value = (value * 100 + (value >> 16)) & 0x0000_FFFF_0000_FFFF;
+    value = (value * 10_000 + (value >> 32)) & 0x0000_0000_FFFF_FFFF;
+
+    Some(value as u32)
+}
+
+/// Convert a byte into its numeric value in `radix`, which must be between 2 and 36.
+///
+/// Letters represent the values 10 through 35, ignoring case.
+///
+/// Returns `None` if `byte` is not a digit in `radix`.
+#[inline]
+pub fn radix_value(byte: u8, radix: u8) -> Option<u8> {
+    debug_assert!(radix > 1 && radix < 37, "radix must be between 2 and 36");
+
+    let value = if byte > b'/' && byte < b':' {
+        byte - b'0'
+    } else if byte > b'@' && byte < b'[' {
+        byte - b'7'
+    } else if byte > 0x60 && byte < 0x7B {
+        byte - b'W'
+    } else {
+        return None;
+    };
+
+    if value < radix {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Determine the radix indicated by the byte following a leading `0`.
+///
+/// Returns `None` if `byte` is not one of `x`, `o` or `b`, ignoring case.
+#[inline]
+pub fn prefix_radix(byte: u8) -> Option<u8> {
+    match byte {
+        b'x' | b'X' => Some(16),
+        b'o' | b'O' => Some(8),
+        b'b' | b'B' => Some(2),
+        _ => None
+    }
+}
+
+/// Digit count and leading zero policy for the numeric collectors.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DigitPolicy {
+    /// Allow a number with more than one digit to start with `0`.
+    pub leading_zeros: bool,
+
+    /// Maximum amount of digits.
+    pub max_digits: usize,
+
+    /// Minimum amount of digits.
+    pub min_digits: usize
+}
+
+impl DigitPolicy {
+    /// Accept any amount of digits, including leading zeros.
+    pub const UNBOUNDED: DigitPolicy = DigitPolicy{
+        leading_zeros: true,
+        max_digits:    usize::MAX,
+        min_digits:    0
+    };
+
+    /// Create a policy that accepts at most `digits` digits.
+    pub fn max(digits: usize) -> DigitPolicy {
+        DigitPolicy{
+            max_digits: digits,
+            ..DigitPolicy::UNBOUNDED
+        }
+    }
+
+    /// Create a policy that accepts exactly `digits` digits.
+    pub fn exact(digits: usize) -> DigitPolicy {
+        DigitPolicy{
+            max_digits: digits,
+            min_digits: digits,
+            ..DigitPolicy::UNBOUNDED
+        }
+    }
+
+    /// Indicates that the digit at position `digits` (starting at 1) may be collected, given
+    /// whether the first digit was `0`.
+    #[inline]
+    pub fn allows(&self, digits: usize, leading_zero: bool) -> bool {
+        digits <= self.max_digits && (self.leading_zeros || digits < 2 || !leading_zero)
+    }
+
+    /// Indicates that collection may finish after `digits` digits.
+    #[inline]
+    pub fn is_satisfied(&self, digits: usize) -> bool {
+        digits >= self.min_digits
+    }
+}
+
+impl Default for DigitPolicy {
+    fn default() -> DigitPolicy {
+        DigitPolicy::UNBOUNDED
+    }
+}
+
+/// Decimal floating-point format options.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FloatFormat {
+    /// Accept a fraction without an integer part, such as `.5`.
+    pub leading_dot: bool,
+
+    /// Accept an integer part followed by a dot without a fraction, such as `5.`.
+    pub trailing_dot: bool,
+
+    /// Accept `inf`, `infinity` and `nan`, ignoring case.
+    pub special: bool
+}
+
+impl FloatFormat {
+    /// Accept only numbers with digits on both sides of the dot, and no special values.
+    pub const STRICT: FloatFormat = FloatFormat{
+        leading_dot:  false,
+        trailing_dot: false,
+        special:      false
+    };
+
+    /// Accept all supported forms.
+    pub const RELAXED: FloatFormat = FloatFormat{
+        leading_dot:  true,
+        trailing_dot: true,
+        special:      true
+    };
+}
+
+impl Default for FloatFormat {
+    fn default() -> FloatFormat {
+        FloatFormat::STRICT
+    }
+}
+
+/// Float scanning result.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum FloatScan {
+    /// Scanning stopped upon locating a byte that is not part of the number.
+    Done,
+
+    /// End-of-stream was located. Indicates whether the scanned bytes form a complete number.
+    Eos(bool),
+
+    /// A byte was located that cannot appear at its position within the number.
+    Invalid
+}
+
+/// Float scanning state.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum FloatState {
+    Start,
+    Sign,
+    Integer,
+    Dot(bool),
+    Fraction,
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+    Special(&'static [u8], usize)
+}
+
+impl FloatState {
+    /// Indicates that a number ending in this state is complete.
+    fn is_complete(self, format: &FloatFormat) -> bool {
+        match self {
+            FloatState::Integer | FloatState::Fraction | FloatState::ExponentDigits => true,
+            FloatState::Dot(integer) => integer && format.trailing_dot,
+            FloatState::Special(word, length) => length == word.len() || length == 3,
+            _ => false
+        }
+    }
+}
+
+/// Scan a decimal float from the start of `bytes`.
+///
+/// Returns the amount of bytes that belong to the number, along with the reason scanning stopped.
+pub(crate) fn scan_float(bytes: &[u8], format: &FloatFormat) -> (usize, FloatScan) {
+    let mut state = FloatState::Start;
+
+    for (index, byte) in bytes.iter().enumerate() {
+        let lower = byte.to_ascii_lowercase();
+
+        state = match state {
+            FloatState::Start if *byte == b'-' || *byte == b'+' => FloatState::Sign,
+            FloatState::Start | FloatState::Sign => {
+                if is_digit!(*byte) {
+                    FloatState::Integer
+                } else if *byte == b'.' && format.leading_dot {
+                    FloatState::Dot(false)
+                } else if lower == b'i' && format.special {
+                    FloatState::Special(b"infinity", 1)
+                } else if lower == b'n' && format.special {
+                    FloatState::Special(b"nan", 1)
+                } else {
+                    return (index, FloatScan::Invalid);
+                }
+            },
+            FloatState::Integer => {
+                if is_digit!(*byte) {
+                    FloatState::Integer
+                } else if *byte == b'.' {
+                    FloatState::Dot(true)
+                } else if lower == b'e' {
+                    FloatState::Exponent
+                } else {
+                    return (index, FloatScan::Done);
+                }
+            },
+            FloatState::Dot(integer) => {
+                if is_digit!(*byte) {
+                    FloatState::Fraction
+                } else if !integer || !format.trailing_dot {
+                    return (index, FloatScan::Invalid);
+                } else if lower == b'e' {
+                    FloatState::Exponent
+                } else {
+                    return (index, FloatScan::Done);
+                }
+            },
+            FloatState::Fraction => {
+                if is_digit!(*byte) {
+                    FloatState::Fraction
+                } else if lower == b'e' {
+                    FloatState::Exponent
+                } else {
+                    return (index, FloatScan::Done);
+                }
+            },
+            FloatState::Exponent if *byte == b'-' || *byte == b'+' => FloatState::ExponentSign,
+            FloatState::Exponent | FloatState::ExponentSign => {
+                if is_digit!(*byte) {
+                    FloatState::ExponentDigits
+                } else {
+                    return (index, FloatScan::Invalid);
+                }
+            },
+            FloatState::ExponentDigits => {
+                if is_digit!(*byte) {
+                    FloatState::ExponentDigits
+                } else {
+                    return (index, FloatScan::Done);
+                }
+            },
+            FloatState::Special(word, length) => {
+                if length < word.len() && lower == word[length] {
+                    FloatState::Special(word, length + 1)
+                } else if state.is_complete(format) {
+                    return (index, FloatScan::Done);
+                } else {
+                    return (index, FloatScan::Invalid);
+                }
+            }
+        };
+    }
+
+    (bytes.len(), FloatScan::Eos(state.is_complete(format)))
+}
+
+/// Parse a float from bytes that have been validated by `scan_float()`.
+pub(crate) fn parse_float<T: FromStr>(bytes: &[u8]) -> Option<T> {
+    str::from_utf8(bytes).ok()?.parse().ok()
+}
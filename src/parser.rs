@@ -0,0 +1,117 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Resumable parsing.
+//!
+//! A resumable parser keeps its position in a state enum that persists between calls. Each call
+//! runs the state machine over the bytes at hand with `bs_machine!()`, moving between states with
+//! `bs_transition!()`. Upon locating end-of-stream, `bs_need_more!()` returns
+//! `ParseResult::NeedMore`, and the next call resumes in the same state with the next bytes:
+//!
+//! ```
+//! #[macro_use]
+//! extern crate byte_slice;
+//!
+//! use byte_slice::{ ByteStream, ParseResult };
+//!
+//! #[derive(Clone, Copy)]
+//! enum State {
+//!     Name,
+//!     Value
+//! }
+//!
+//! struct Header {
+//!     name:  Vec<u8>,
+//!     state: State,
+//!     value: Vec<u8>
+//! }
+//!
+//! impl Header {
+//!     fn parse(&mut self, bytes: &[u8]) -> ParseResult<(), &'static str> {
+//!         let mut context = ByteStream::new(bytes);
+//!
+//!         bs_machine!(context, self.state, {
+//!             State::Name => {
+//!                 bs_collect_until!(context, context.byte == b':', {
+//!                     self.name.extend_from_slice(bs_slice!(context));
+//!
+//!                     bs_need_more!();
+//!                 });
+//!
+//!                 self.name.extend_from_slice(bs_slice_ignore!(context));
+//!
+//!                 bs_transition!(context, self.state, State::Value);
+//!             },
+//!             State::Value => {
+//!                 bs_collect_until!(context, context.byte == b'\n', {
+//!                     self.value.extend_from_slice(bs_slice!(context));
+//!
+//!                     bs_need_more!();
+//!                 });
+//!
+//!                 self.value.extend_from_slice(bs_slice_ignore!(context));
+//!
+//!                 return ParseResult::Done(());
+//!             }
+//!         })
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut header = Header{ name: Vec::new(), state: State::Name, value: Vec::new() };
+//!
+//!     assert_eq!(header.parse(b"Ho"), ParseResult::NeedMore);
+//!     assert_eq!(header.parse(b"st:exam"), ParseResult::NeedMore);
+//!     assert_eq!(header.parse(b"ple.com\n"), ParseResult::Done(()));
+//!     assert_eq!(header.name, b"Host");
+//!     assert_eq!(header.value, b"example.com");
+//! }
+//! ```
+
+/// Resumable parse result.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseResult<T, E> {
+    /// Parsing finished with a value.
+    Done(T),
+
+    /// Parsing failed.
+    Error(E),
+
+    /// End-of-stream was located, and parsing will resume in the current state with more bytes.
+    NeedMore
+}
+
+impl<T, E> ParseResult<T, E> {
+    /// Indicates that parsing finished with a value.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        matches!(*self, ParseResult::Done(_))
+    }
+
+    /// Indicates that parsing failed.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        matches!(*self, ParseResult::Error(_))
+    }
+
+    /// Indicates that more bytes are needed.
+    #[inline]
+    pub fn is_need_more(&self) -> bool {
+        matches!(*self, ParseResult::NeedMore)
+    }
+}
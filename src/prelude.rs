@@ -0,0 +1,28 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Traits that provide the stream methods.
+//!
+//! The stream methods are provided by `ByteContext` and `ByteContextMut` rather than being inherent
+//! to the stream types, so they only resolve when the traits are in scope:
+//!
+//! ```
+//! use byte_slice::prelude::*;
+//! ```
+
+pub use { ByteContext, ByteContextMut };
@@ -0,0 +1,1035 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Byte searching.
+//!
+//! On x86_64 the search functions use SSE2, or AVX2 when it's detected at runtime. All other
+//! targets search a word at a time.
+
+use set::ByteSet;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::mem;
+use std::ptr;
+
+const WORD: usize = mem::size_of::<usize>();
+
+const LO: usize = usize::MAX / 0xFF;
+
+const HI: usize = LO * 0x80;
+
+/// Precompiled substring searcher.
+///
+/// A `Finder` is built once for a pattern, and can then be used to search any amount of
+/// haystacks. Searching locates candidates using the vectorized byte search on the rarest byte of
+/// the pattern, and falls back to Boyer-Moore-Horspool when candidates are frequent.
+#[derive(Clone, Debug)]
+pub struct Finder<'a> {
+    /// Pattern.
+    pattern: Cow<'a, [u8]>,
+
+    /// Index of the rarest byte within the pattern.
+    rare: usize,
+
+    /// Horspool shift for each byte.
+    skip: [usize; 256]
+}
+
+impl<'a> Finder<'a> {
+    /// Create a new `Finder` for `pattern`.
+    pub fn new(pattern: &'a [u8]) -> Finder<'a> {
+        let mut skip = [pattern.len(); 256];
+
+        for (n, byte) in pattern.iter().enumerate() {
+            if n + 1 < pattern.len() {
+                skip[*byte as usize] = pattern.len() - 1 - n;
+            }
+        }
+
+        Finder{
+            pattern: Cow::Borrowed(pattern),
+            rare:    rarest(pattern),
+            skip
+        }
+    }
+
+    /// Convert this `Finder` into one that owns its pattern.
+    pub fn into_owned(self) -> Finder<'static> {
+        Finder{
+            pattern: Cow::Owned(self.pattern.into_owned()),
+            rare:    self.rare,
+            skip:    self.skip
+        }
+    }
+
+    /// Retrieve the pattern.
+    #[inline]
+    pub fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    /// Find the first occurrence of the pattern within `haystack` and return its index.
+    ///
+    /// An empty pattern is never found.
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let length     = self.pattern.len();
+        let mut index  = 0;
+        let mut misses = 0;
+
+        if length == 0 {
+            return None;
+        }
+
+        while index + length <= haystack.len() {
+            // once candidates become frequent, the prefilter costs more than it saves
+            if misses > 8 && index < misses * length {
+                return self.find_horspool(haystack, index);
+            }
+
+            let start = match memchr(self.pattern[self.rare],
+                                     &haystack[index + self.rare..haystack.len() - length
+                                                                + self.rare + 1]) {
+                Some(n) => index + n,
+                None => return None
+            };
+
+            if haystack[start..start + length] == *self.pattern {
+                return Some(start);
+            }
+
+            index   = start + self.skip[haystack[start + length - 1] as usize];
+            misses += 1;
+        }
+
+        None
+    }
+
+    /// Find the first occurrence of the pattern within `haystack` starting at `index`, using
+    /// Boyer-Moore-Horspool.
+    fn find_horspool(&self, haystack: &[u8], mut index: usize) -> Option<usize> {
+        let last = self.pattern.len() - 1;
+
+        while index + last < haystack.len() {
+            let byte = haystack[index + last];
+
+            if byte == self.pattern[last] && haystack[index..index + last] == self.pattern[..last] {
+                return Some(index);
+            }
+
+            index += self.skip[byte as usize];
+        }
+
+        None
+    }
+}
+
+/// Precompiled reverse substring searcher.
+///
+/// A `FinderRev` is the reverse counterpart of `Finder`, and locates the last occurrence of its
+/// pattern within each haystack, falling back to reverse Boyer-Moore-Horspool when candidates are
+/// frequent.
+#[derive(Clone, Debug)]
+pub struct FinderRev<'a> {
+    /// Pattern.
+    pattern: Cow<'a, [u8]>,
+
+    /// Index of the rarest byte within the pattern.
+    rare: usize,
+
+    /// Reverse Horspool shift for each byte.
+    rskip: [usize; 256]
+}
+
+impl<'a> FinderRev<'a> {
+    /// Create a new `FinderRev` for `pattern`.
+    pub fn new(pattern: &'a [u8]) -> FinderRev<'a> {
+        let mut rskip = [pattern.len(); 256];
+
+        for (n, byte) in pattern.iter().enumerate().skip(1).rev() {
+            rskip[*byte as usize] = n;
+        }
+
+        FinderRev{
+            pattern: Cow::Borrowed(pattern),
+            rare:    rarest(pattern),
+            rskip
+        }
+    }
+
+    /// Convert this `FinderRev` into one that owns its pattern.
+    pub fn into_owned(self) -> FinderRev<'static> {
+        FinderRev{
+            pattern: Cow::Owned(self.pattern.into_owned()),
+            rare:    self.rare,
+            rskip:   self.rskip
+        }
+    }
+
+    /// Retrieve the pattern.
+    #[inline]
+    pub fn pattern(&self) -> &[u8] {
+        &self.pattern
+    }
+
+    /// Find the last occurrence of the pattern within `haystack` and return its index.
+    ///
+    /// An empty pattern is never found.
+    pub fn rfind(&self, haystack: &[u8]) -> Option<usize> {
+        let length     = self.pattern.len();
+        let mut misses = 0;
+
+        if length == 0 || length > haystack.len() {
+            return None;
+        }
+
+        // candidates start before end
+        let mut end = haystack.len() - length + 1;
+
+        while end > 0 {
+            if misses > 8 && haystack.len() - end < misses * length {
+                return self.rfind_horspool(haystack, end);
+            }
+
+            let start = memrchr(self.pattern[self.rare], &haystack[self.rare..end + self.rare])?;
+
+            if haystack[start..start + length] == *self.pattern {
+                return Some(start);
+            }
+
+            let shift = self.rskip[haystack[start] as usize];
+
+            if shift > start {
+                return None;
+            }
+
+            end     = start - shift + 1;
+            misses += 1;
+        }
+
+        None
+    }
+
+    /// Find the last occurrence of the pattern within `haystack` starting before `end`, using
+    /// reverse Boyer-Moore-Horspool.
+    fn rfind_horspool(&self, haystack: &[u8], mut end: usize) -> Option<usize> {
+        let length = self.pattern.len();
+
+        while end > 0 {
+            let start = end - 1;
+            let byte  = haystack[start];
+
+            if byte == self.pattern[0]
+            && haystack[start + 1..start + length] == self.pattern[1..] {
+                return Some(start);
+            }
+
+            let shift = self.rskip[byte as usize];
+
+            if shift > start {
+                return None;
+            }
+
+            end = start - shift + 1;
+        }
+
+        None
+    }
+}
+
+/// Multi-pattern match semantics.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchKind {
+    /// Report the match that starts first, preferring the pattern that was supplied first when
+    /// several patterns start at the same index.
+    LeftmostFirst,
+
+    /// Report the match that starts first, preferring the longest pattern when several patterns
+    /// start at the same index.
+    LeftmostLongest
+}
+
+/// Multi-pattern match.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Match {
+    /// Index of the first byte after the match.
+    pub end: usize,
+
+    /// Pattern id, which is the index of the pattern as it was supplied.
+    pub pattern: usize,
+
+    /// Index of the first byte of the match.
+    pub start: usize
+}
+
+/// Multi-pattern automaton state.
+#[derive(Clone, Debug)]
+struct State {
+    /// Length of the longest pattern prefix that this state represents.
+    depth: usize,
+
+    /// Next state for each byte.
+    next: [u32; 256],
+
+    /// Ids of the patterns that end at this state.
+    outputs: Vec<usize>
+}
+
+impl State {
+    fn new(depth: usize) -> State {
+        State{
+            depth,
+            next:    [0; 256],
+            outputs: Vec::new()
+        }
+    }
+}
+
+/// Precompiled multi-pattern searcher, built as an Aho-Corasick automaton.
+///
+/// A `MultiFinder` is built once for a set of patterns, and can then be used to search any amount
+/// of haystacks in a single pass. Empty patterns are never found.
+#[derive(Clone, Debug)]
+pub struct MultiFinder {
+    /// Match semantics.
+    kind: MatchKind,
+
+    /// Length of each pattern.
+    lengths: Vec<usize>,
+
+    /// Automaton states, starting with the root.
+    states: Vec<State>
+}
+
+impl MultiFinder {
+    /// Create a new `MultiFinder` for `patterns`, using `kind` match semantics.
+    pub fn new<I, P>(patterns: I, kind: MatchKind) -> MultiFinder
+    where I: IntoIterator<Item = P>, P: AsRef<[u8]> {
+        let mut lengths = Vec::new();
+        let mut states  = vec![State::new(0)];
+
+        // build the trie, where a transition to the root means that no transition exists yet
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let pattern   = pattern.as_ref();
+            let mut state = 0;
+
+            for byte in pattern {
+                if states[state].next[*byte as usize] == 0 {
+                    states.push(State::new(states[state].depth + 1));
+
+                    states[state].next[*byte as usize] = (states.len() - 1) as u32;
+                }
+
+                state = states[state].next[*byte as usize] as usize;
+            }
+
+            if !pattern.is_empty() {
+                states[state].outputs.push(id);
+            }
+
+            lengths.push(pattern.len());
+        }
+
+        // convert the trie into a complete automaton in breadth-first order, so that each failure
+        // state has been completed before it's used
+        let mut fail  = vec![0; states.len()];
+        let mut queue = VecDeque::new();
+
+        for byte in 0..256 {
+            let next = states[0].next[byte] as usize;
+
+            if next != 0 {
+                queue.push_back(next);
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let outputs = states[fail[state]].outputs.clone();
+
+            states[state].outputs.extend(outputs);
+            states[state].outputs.sort();
+
+            for byte in 0..256 {
+                let next = states[state].next[byte] as usize;
+
+                if next != 0 {
+                    fail[next] = states[fail[state]].next[byte] as usize;
+
+                    queue.push_back(next);
+                } else {
+                    states[state].next[byte] = states[fail[state]].next[byte];
+                }
+            }
+        }
+
+        MultiFinder{
+            kind,
+            lengths,
+            states
+        }
+    }
+
+    /// Retrieve the match semantics.
+    #[inline]
+    pub fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Find the leftmost occurrence of any pattern within `haystack`.
+    pub fn find(&self, haystack: &[u8]) -> Option<Match> {
+        let mut best: Option<Match> = None;
+        let mut state = 0;
+
+        for (index, byte) in haystack.iter().enumerate() {
+            state = self.states[state].next[*byte as usize] as usize;
+
+            for pattern in &self.states[state].outputs {
+                let end       = index + 1;
+                let candidate = Match{
+                    end,
+                    pattern: *pattern,
+                    start:   end - self.lengths[*pattern]
+                };
+
+                best = match best {
+                    Some(m) if !self.is_preferred(&candidate, &m) => Some(m),
+                    _ => Some(candidate)
+                };
+            }
+
+            // a match that hasn't been located yet cannot start before the longest prefix
+            if let Some(m) = best {
+                if index + 1 - self.states[state].depth > m.start {
+                    return best;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Indicates that `candidate` is preferred over `current`.
+    #[inline]
+    fn is_preferred(&self, candidate: &Match, current: &Match) -> bool {
+        if candidate.start != current.start {
+            return candidate.start < current.start;
+        }
+
+        match self.kind {
+            MatchKind::LeftmostFirst => candidate.pattern < current.pattern,
+            MatchKind::LeftmostLongest => {
+                candidate.end > current.end
+                || (candidate.end == current.end && candidate.pattern < current.pattern)
+            }
+        }
+    }
+}
+
+/// Index that ends a chain of `Keywords` candidates.
+const NO_KEYWORD: u16 = u16::MAX;
+
+/// Compile-time keyword matching table.
+///
+/// A `Keywords` table is built from non-empty keywords in a `const` context, and chains them by
+/// their leading byte, so that matching only compares the keywords that share the first byte of the
+/// input, and only when enough bytes remain. Each chain is ordered from the longest keyword to the
+/// shortest, so that the longest matching keyword is found regardless of the order the keywords
+/// were supplied in. Among equal keywords, the first supplied is found.
+#[derive(Clone, Copy, Debug)]
+pub struct Keywords<const N: usize> {
+    /// First keyword for each leading byte.
+    first: [u16; 256],
+
+    /// Keywords.
+    keywords: [&'static [u8]; N],
+
+    /// Next keyword with the same leading byte.
+    next: [u16; N]
+}
+
+impl<const N: usize> Keywords<N> {
+    /// Create a new `Keywords` table for `keywords`.
+    ///
+    /// Panics, which fails compilation in a `const` context, when a keyword is empty or when there
+    /// are 65535 keywords or more.
+    pub const fn new(keywords: [&'static [u8]; N]) -> Keywords<N> {
+        let mut first = [NO_KEYWORD; 256];
+        let mut next  = [NO_KEYWORD; N];
+        let mut n     = 0;
+
+        assert!(N < NO_KEYWORD as usize, "too many keywords");
+
+        while n < N {
+            assert!(!keywords[n].is_empty(), "keywords must not be empty");
+
+            let byte     = keywords[n][0] as usize;
+            let mut m    = first[byte];
+            let mut prev = NO_KEYWORD;
+
+            // insert after every keyword that's at least as long, so the longest match is found
+            while m != NO_KEYWORD && keywords[m as usize].len() >= keywords[n].len() {
+                prev = m;
+                m    = next[m as usize];
+            }
+
+            if prev == NO_KEYWORD {
+                first[byte] = n as u16;
+            } else {
+                next[prev as usize] = n as u16;
+            }
+
+            next[n] = m;
+            n      += 1;
+        }
+
+        Keywords{
+            first,
+            keywords,
+            next
+        }
+    }
+
+    /// Find the longest keyword that `bytes` starts with, and return its index.
+    pub fn find(&self, bytes: &[u8]) -> Option<usize> {
+        let mut n = self.first[*bytes.first()? as usize];
+
+        while n != NO_KEYWORD {
+            let keyword = self.keywords[n as usize];
+
+            if keyword.len() <= bytes.len() && bytes[1..keyword.len()] == keyword[1..] {
+                return Some(n as usize);
+            }
+
+            n = self.next[n as usize];
+        }
+
+        None
+    }
+
+    /// Retrieve the keywords.
+    #[inline]
+    pub fn keywords(&self) -> &[&'static [u8]] {
+        &self.keywords
+    }
+}
+
+/// Estimate how common `byte` is in typical text and protocol data.
+///
+/// Lower ranks are rarer.
+fn rank(byte: u8) -> u8 {
+    match byte {
+        b' ' | b'a'..=b'z' => 4,
+        b'0'..=b'9' | b'-' | b'\r' | b'\n' => 3,
+        b'A'..=b'Z' | 0x21..=0x7E | b'\t' | 0x00 => 2,
+        0x80..=0xFF => 1,
+        _ => 0
+    }
+}
+
+/// Retrieve the index of the rarest byte within `pattern`.
+fn rarest(pattern: &[u8]) -> usize {
+    let mut rare = 0;
+
+    for (n, byte) in pattern.iter().enumerate() {
+        if rank(*byte) < rank(pattern[rare]) {
+            rare = n;
+        }
+    }
+
+    rare
+}
+
+/// Indicates that `a` and `b` are equal, ignoring ASCII case.
+///
+/// Only the ASCII letters are folded, so bytes of `0x80` and above must match exactly. Eight bytes
+/// are folded at a time.
+pub fn eq_ignore_case(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut index = 0;
+
+    while index + 8 <= a.len() {
+        let mut x = [0; 8];
+        let mut y = [0; 8];
+
+        x.copy_from_slice(&a[index..index + 8]);
+        y.copy_from_slice(&b[index..index + 8]);
+
+        if lower8(u64::from_ne_bytes(x)) != lower8(u64::from_ne_bytes(y)) {
+            return false;
+        }
+
+        index += 8;
+    }
+
+    a[index..].eq_ignore_ascii_case(&b[index..])
+}
+
+/// Find the first occurrence of `pattern` within `haystack`, ignoring ASCII case, and return its
+/// index.
+///
+/// An empty pattern is never found.
+pub fn find_ignore_case(pattern: &[u8], haystack: &[u8]) -> Option<usize> {
+    let mut index = 0;
+
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let lower = pattern[0].to_ascii_lowercase();
+    let upper = pattern[0].to_ascii_uppercase();
+
+    while index + pattern.len() <= haystack.len() {
+        let end = haystack.len() - pattern.len() + 1;
+
+        index += memchr2(lower, upper, &haystack[index..end])?;
+
+        if eq_ignore_case(&haystack[index..index + pattern.len()], pattern) {
+            return Some(index);
+        }
+
+        index += 1;
+    }
+
+    None
+}
+
+/// Convert the ASCII uppercase letters within eight bytes to lowercase.
+#[inline]
+fn lower8(word: u64) -> u64 {
+    const LO64: u64 = 0x0101_0101_0101_0101;
+    const HI64: u64 = 0x8080_8080_8080_8080;
+
+    // adding to the low seven bits of each byte never carries into the next byte, and sets the
+    // high bit when the byte is above the threshold
+    let heptets  = word & !HI64;
+    let above_z  = heptets + LO64 * (0x7F - b'Z' as u64);
+    let from_a   = heptets + LO64 * (0x80 - b'A' as u64);
+    let is_upper = !word & (from_a ^ above_z) & HI64;
+
+    word | (is_upper >> 2)
+}
+
+/// Retrieve the first `N` bytes of `pattern` as an array.
+///
+/// Returns `None` when `pattern` is shorter than `N` bytes.
+#[inline]
+pub fn prefix<const N: usize>(pattern: &[u8]) -> Option<&[u8; N]> {
+    if pattern.len() < N {
+        None
+    } else {
+        Some(unsafe { prefix_unchecked(pattern) })
+    }
+}
+
+/// Retrieve the first `N` bytes of `pattern` as an array, without checking its length.
+///
+/// # Safety
+///
+/// `pattern` must be at least `N` bytes long. With the `debug-checks` feature enabled, this is
+/// asserted in debug builds.
+#[inline(always)]
+pub unsafe fn prefix_unchecked<const N: usize>(pattern: &[u8]) -> &[u8; N] {
+    #[cfg(feature = "debug-checks")]
+    debug_assert!(pattern.len() >= N, "pattern is shorter than {} bytes", N);
+
+    &*(pattern.as_ptr() as *const [u8; N])
+}
+
+/// Indicates that the `N` bytes of `haystack` at `index` are equal to `pattern`.
+///
+/// Bytes are compared using the widest unaligned loads that fit, so that for a constant `N` the
+/// comparison compiles down to a handful of `u128`, `u64`, `u32`, `u16` and `u8` compares.
+///
+/// # Safety
+///
+/// `N` bytes must be available within `haystack` at `index`. With the `debug-checks` feature
+/// enabled, this is asserted in debug builds.
+#[inline(always)]
+pub unsafe fn starts_with_unchecked<const N: usize>(
+    haystack: &[u8], index: usize, pattern: &[u8; N]
+) -> bool {
+    #[cfg(feature = "debug-checks")]
+    debug_assert!(index + N <= haystack.len(), "{} bytes are not available at index {}", N, index);
+
+    let haystack  = haystack.as_ptr().add(index);
+    let pattern   = pattern.as_ptr();
+    let mut index = 0;
+
+    while index + 16 <= N {
+        if !eq_word::<u128>(haystack.add(index), pattern.add(index)) {
+            return false;
+        }
+
+        index += 16;
+    }
+
+    if index + 8 <= N {
+        if !eq_word::<u64>(haystack.add(index), pattern.add(index)) {
+            return false;
+        }
+
+        index += 8;
+    }
+
+    if index + 4 <= N {
+        if !eq_word::<u32>(haystack.add(index), pattern.add(index)) {
+            return false;
+        }
+
+        index += 4;
+    }
+
+    if index + 2 <= N {
+        if !eq_word::<u16>(haystack.add(index), pattern.add(index)) {
+            return false;
+        }
+
+        index += 2;
+    }
+
+    index == N || *haystack.add(index) == *pattern.add(index)
+}
+
+/// Indicates that the words at `a` and `b` are equal.
+#[inline(always)]
+unsafe fn eq_word<T: PartialEq>(a: *const u8, b: *const u8) -> bool {
+    ptr::read_unaligned(a as *const T) == ptr::read_unaligned(b as *const T)
+}
+
+/// Find the first occurrence of `a` within `haystack` and return its index.
+#[inline]
+pub fn memchr(a: u8, haystack: &[u8]) -> Option<usize> {
+    find_any(haystack, [a])
+}
+
+/// Find the first occurrence of `a` or `b` within `haystack` and return its index.
+#[inline]
+pub fn memchr2(a: u8, b: u8, haystack: &[u8]) -> Option<usize> {
+    find_any(haystack, [a, b])
+}
+
+/// Find the first occurrence of `a`, `b` or `c` within `haystack` and return its index.
+#[inline]
+pub fn memchr3(a: u8, b: u8, c: u8, haystack: &[u8]) -> Option<usize> {
+    find_any(haystack, [a, b, c])
+}
+
+/// Find the last occurrence of `a` within `haystack` and return its index.
+#[inline]
+pub fn memrchr(a: u8, haystack: &[u8]) -> Option<usize> {
+    rfind_any(haystack, [a])
+}
+
+/// Retrieve the length of the run of bytes at the start of `haystack` that are in `set`.
+///
+/// On x86_64 sets of up to 16 bytes are scanned 16 bytes at a time using SSE2. All other sets are
+/// scanned a byte at a time, using the set's bitmap as a lookup table.
+pub fn span(set: &ByteSet, haystack: &[u8]) -> usize {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if haystack.len() >= 16 {
+            if let Some((members, length)) = set.small_members() {
+                return unsafe { x86::span_sse2(haystack, &members[..length]) };
+            }
+        }
+    }
+
+    span_scalar(set, haystack)
+}
+
+/// Retrieve the length of the run of bytes at the start of `haystack` that are in `set`, a byte at
+/// a time.
+#[inline]
+fn span_scalar(set: &ByteSet, haystack: &[u8]) -> usize {
+    haystack.iter().position(|byte| !set.contains(*byte)).unwrap_or(haystack.len())
+}
+
+/// Find the first occurrence of any of `needles` within `haystack`, using the fastest
+/// implementation available.
+#[inline]
+fn find_any<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if haystack.len() >= 32 && is_x86_feature_detected!("avx2") {
+            return unsafe { x86::find_avx2(haystack, needles) };
+        } else if haystack.len() >= 16 {
+            return unsafe { x86::find_sse2(haystack, needles) };
+        }
+    }
+
+    find_swar(haystack, needles)
+}
+
+/// Find the last occurrence of any of `needles` within `haystack`, using the fastest implementation
+/// available.
+#[inline]
+fn rfind_any<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if haystack.len() >= 32 && is_x86_feature_detected!("avx2") {
+            return unsafe { x86::rfind_avx2(haystack, needles) };
+        } else if haystack.len() >= 16 {
+            return unsafe { x86::rfind_sse2(haystack, needles) };
+        }
+    }
+
+    rfind_swar(haystack, needles)
+}
+
+/// Find the first occurrence of any of `needles` within `haystack`, one byte at a time.
+#[inline]
+fn find_scalar<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    haystack.iter().position(|byte| needles.contains(byte))
+}
+
+/// Find the first occurrence of any of `needles` within `haystack`, a word at a time.
+pub(crate) fn find_swar<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    let mut splats = [0; N];
+
+    for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+        *splat = LO * *needle as usize;
+    }
+
+    let mut index = 0;
+
+    while index + WORD <= haystack.len() {
+        let mut word = [0; WORD];
+
+        word.copy_from_slice(&haystack[index..index + WORD]);
+
+        let word = usize::from_ne_bytes(word);
+
+        // a byte is zero after xor with a splatted needle only when it matches that needle
+        if splats.iter().any(|splat| {
+            let x = word ^ splat;
+
+            x.wrapping_sub(LO) & !x & HI != 0
+        }) {
+            break;
+        }
+
+        index += WORD;
+    }
+
+    find_scalar(&haystack[index..], needles).map(|n| n + index)
+}
+
+/// Find the last occurrence of any of `needles` within `haystack`, one byte at a time.
+#[inline]
+fn rfind_scalar<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    haystack.iter().rposition(|byte| needles.contains(byte))
+}
+
+/// Find the last occurrence of any of `needles` within `haystack`, a word at a time.
+pub(crate) fn rfind_swar<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+    let mut splats = [0; N];
+
+    for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+        *splat = LO * *needle as usize;
+    }
+
+    let mut end = haystack.len();
+
+    while end >= WORD {
+        let mut word = [0; WORD];
+
+        word.copy_from_slice(&haystack[end - WORD..end]);
+
+        let word = usize::from_ne_bytes(word);
+
+        if splats.iter().any(|splat| {
+            let x = word ^ splat;
+
+            x.wrapping_sub(LO) & !x & HI != 0
+        }) {
+            break;
+        }
+
+        end -= WORD;
+    }
+
+    rfind_scalar(&haystack[..end], needles)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    /// Find the first occurrence of any of `needles` within `haystack`, 32 bytes at a time.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn find_avx2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+        let mut splats = [_mm256_setzero_si256(); N];
+
+        for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+            *splat = _mm256_set1_epi8(*needle as i8);
+        }
+
+        let mut index = 0;
+
+        while index + 32 <= haystack.len() {
+            let chunk = _mm256_loadu_si256(haystack.as_ptr().add(index) as *const __m256i);
+            let mut eq = _mm256_setzero_si256();
+
+            for splat in splats.iter() {
+                eq = _mm256_or_si256(eq, _mm256_cmpeq_epi8(chunk, *splat));
+            }
+
+            let mask = _mm256_movemask_epi8(eq);
+
+            if mask != 0 {
+                return Some(index + mask.trailing_zeros() as usize);
+            }
+
+            index += 32;
+        }
+
+        find_sse2(&haystack[index..], needles).map(|n| n + index)
+    }
+
+    /// Find the first occurrence of any of `needles` within `haystack`, 16 bytes at a time.
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn find_sse2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+        let mut splats = [_mm_setzero_si128(); N];
+
+        for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+            *splat = _mm_set1_epi8(*needle as i8);
+        }
+
+        let mut index = 0;
+
+        while index + 16 <= haystack.len() {
+            let chunk = _mm_loadu_si128(haystack.as_ptr().add(index) as *const __m128i);
+            let mut eq = _mm_setzero_si128();
+
+            for splat in splats.iter() {
+                eq = _mm_or_si128(eq, _mm_cmpeq_epi8(chunk, *splat));
+            }
+
+            let mask = _mm_movemask_epi8(eq);
+
+            if mask != 0 {
+                return Some(index + mask.trailing_zeros() as usize);
+            }
+
+            index += 16;
+        }
+
+        super::find_scalar(&haystack[index..], needles).map(|n| n + index)
+    }
+
+    /// Retrieve the length of the run of bytes at the start of `haystack` that are any of
+    /// `members`, 16 bytes at a time. There must be no more than 16 members.
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn span_sse2(haystack: &[u8], members: &[u8]) -> usize {
+        let mut splats = [_mm_setzero_si128(); 16];
+
+        for (splat, member) in splats.iter_mut().zip(members.iter()) {
+            *splat = _mm_set1_epi8(*member as i8);
+        }
+
+        let splats    = &splats[..members.len()];
+        let mut index = 0;
+
+        while index + 16 <= haystack.len() {
+            let chunk = _mm_loadu_si128(haystack.as_ptr().add(index) as *const __m128i);
+            let mut eq = _mm_setzero_si128();
+
+            for splat in splats.iter() {
+                eq = _mm_or_si128(eq, _mm_cmpeq_epi8(chunk, *splat));
+            }
+
+            // set bits mark the bytes that aren't members
+            let mask = _mm_movemask_epi8(eq) as u32 ^ 0xFFFF;
+
+            if mask != 0 {
+                return index + mask.trailing_zeros() as usize;
+            }
+
+            index += 16;
+        }
+
+        haystack[index..].iter()
+                         .position(|byte| !members.contains(byte))
+                         .map_or(haystack.len(), |n| n + index)
+    }
+
+    /// Find the last occurrence of any of `needles` within `haystack`, 32 bytes at a time.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn rfind_avx2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+        let mut splats = [_mm256_setzero_si256(); N];
+
+        for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+            *splat = _mm256_set1_epi8(*needle as i8);
+        }
+
+        let mut end = haystack.len();
+
+        while end >= 32 {
+            let chunk = _mm256_loadu_si256(haystack.as_ptr().add(end - 32) as *const __m256i);
+            let mut eq = _mm256_setzero_si256();
+
+            for splat in splats.iter() {
+                eq = _mm256_or_si256(eq, _mm256_cmpeq_epi8(chunk, *splat));
+            }
+
+            let mask = _mm256_movemask_epi8(eq) as u32;
+
+            if mask != 0 {
+                return Some(end - 1 - mask.leading_zeros() as usize);
+            }
+
+            end -= 32;
+        }
+
+        rfind_sse2(&haystack[..end], needles)
+    }
+
+    /// Find the last occurrence of any of `needles` within `haystack`, 16 bytes at a time.
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn rfind_sse2<const N: usize>(haystack: &[u8], needles: [u8; N]) -> Option<usize> {
+        let mut splats = [_mm_setzero_si128(); N];
+
+        for (splat, needle) in splats.iter_mut().zip(needles.iter()) {
+            *splat = _mm_set1_epi8(*needle as i8);
+        }
+
+        let mut end = haystack.len();
+
+        while end >= 16 {
+            let chunk = _mm_loadu_si128(haystack.as_ptr().add(end - 16) as *const __m128i);
+            let mut eq = _mm_setzero_si128();
+
+            for splat in splats.iter() {
+                eq = _mm_or_si128(eq, _mm_cmpeq_epi8(chunk, *splat));
+            }
+
+            // the mask only uses the low 16 bits
+            let mask = (_mm_movemask_epi8(eq) as u32) << 16;
+
+            if mask != 0 {
+                return Some(end - 1 - mask.leading_zeros() as usize);
+            }
+
+            end -= 16;
+        }
+
+        super::rfind_scalar(&haystack[..end], needles)
+    }
+}
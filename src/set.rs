@@ -0,0 +1,243 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Byte sets.
+
+use search;
+use std::fmt;
+use std::ops::{ BitAnd, BitOr, Not, Sub };
+
+/// Set of bytes, backed by a 256-bit bitmap.
+///
+/// Sets are `const` constructible from literal bytes and inclusive ranges, so that custom byte
+/// classes can be defined at compile time:
+///
+/// ```
+/// use byte_slice::ByteSet;
+///
+/// const TOKEN: ByteSet = ByteSet::ALPHANUMERIC.with_bytes(b"!#$%&'*+-.^_`|~");
+///
+/// assert!(TOKEN.contains(b'~'));
+/// assert!(!TOKEN.contains(b':'));
+/// ```
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct ByteSet {
+    /// Bitmap, where bit `byte % 64` of word `byte / 64` is set for each byte in the set.
+    bits: [u64; 4]
+}
+
+impl ByteSet {
+    /// Set of the alphabetical bytes, `A-Z` and `a-z`.
+    pub const ALPHA: ByteSet = ByteSet::range(b'A', b'Z').with_range(b'a', b'z');
+
+    /// Set of the alphanumeric bytes, `0-9`, `A-Z` and `a-z`.
+    pub const ALPHANUMERIC: ByteSet = ByteSet::ALPHA.union(ByteSet::DIGIT);
+
+    /// Set of every byte.
+    pub const ALL: ByteSet = ByteSet::EMPTY.complement();
+
+    /// Set of the control bytes, `0x00-0x1F` and `0x7F`.
+    pub const CONTROL: ByteSet = ByteSet::range(0x00, 0x1F).with(0x7F);
+
+    /// Set of the digit bytes, `0-9`.
+    pub const DIGIT: ByteSet = ByteSet::range(b'0', b'9');
+
+    /// Empty set.
+    pub const EMPTY: ByteSet = ByteSet{ bits: [0; 4] };
+
+    /// Set of the hex bytes, `0-9`, `A-F` and `a-f`.
+    pub const HEX: ByteSet = ByteSet::DIGIT.with_range(b'A', b'F').with_range(b'a', b'f');
+
+    /// Set of the bytes that aren't visible 7-bit characters.
+    pub const NOT_VISIBLE_7BIT: ByteSet = ByteSet::VISIBLE_7BIT.complement();
+
+    /// Set of the bytes that aren't visible 8-bit characters.
+    pub const NOT_VISIBLE_8BIT: ByteSet = ByteSet::VISIBLE_8BIT.complement();
+
+    /// Set of the visible 7-bit characters, `0x21-0x7E`. Space is not considered visible.
+    pub const VISIBLE_7BIT: ByteSet = ByteSet::range(0x21, 0x7E);
+
+    /// Set of the visible 8-bit characters, `0x21-0x7E` and `0x80-0xFE`. Space is not considered
+    /// visible.
+    pub const VISIBLE_8BIT: ByteSet = ByteSet::VISIBLE_7BIT.with_range(0x80, 0xFE);
+
+    /// Create a set of `bytes`.
+    pub const fn bytes(bytes: &[u8]) -> ByteSet {
+        ByteSet::EMPTY.with_bytes(bytes)
+    }
+
+    /// Create a set of the bytes from `start` to `end`, inclusive.
+    pub const fn range(start: u8, end: u8) -> ByteSet {
+        ByteSet::EMPTY.with_range(start, end)
+    }
+
+    /// Create a set of the bytes that aren't in this set.
+    pub const fn complement(self) -> ByteSet {
+        ByteSet{
+            bits: [!self.bits[0], !self.bits[1], !self.bits[2], !self.bits[3]]
+        }
+    }
+
+    /// Indicates that `byte` is in this set.
+    #[inline]
+    pub const fn contains(&self, byte: u8) -> bool {
+        self.bits[(byte >> 6) as usize] & (1 << (byte & 0x3F)) != 0
+    }
+
+    /// Create a set of the bytes in this set that aren't in `other`.
+    pub const fn difference(self, other: ByteSet) -> ByteSet {
+        self.intersection(other.complement())
+    }
+
+    /// Create a set of the bytes in both this set and `other`.
+    pub const fn intersection(self, other: ByteSet) -> ByteSet {
+        ByteSet{
+            bits: [self.bits[0] & other.bits[0], self.bits[1] & other.bits[1],
+                   self.bits[2] & other.bits[2], self.bits[3] & other.bits[3]]
+        }
+    }
+
+    /// Indicates that this set is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Retrieve an iterator over the bytes in this set, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..=255u8).filter(move |byte| self.contains(*byte))
+    }
+
+    /// Retrieve the length of the run of bytes at the start of `haystack` that are in this set.
+    #[inline]
+    pub fn span(&self, haystack: &[u8]) -> usize {
+        search::span(self, haystack)
+    }
+
+    /// Retrieve the bytes in this set, in ascending order, along with their amount, when there are
+    /// no more than 16.
+    pub(crate) fn small_members(&self) -> Option<([u8; 16], usize)> {
+        let mut length  = 0;
+        let mut members = [0; 16];
+
+        if self.len() > 16 {
+            return None;
+        }
+
+        for (n, word) in self.bits.iter().enumerate() {
+            let mut word = *word;
+
+            while word != 0 {
+                members[length] = (n * 64 + word.trailing_zeros() as usize) as u8;
+                length         += 1;
+                word           &= word - 1;
+            }
+        }
+
+        Some((members, length))
+    }
+
+    /// Retrieve the amount of bytes in this set.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        (self.bits[0].count_ones() + self.bits[1].count_ones()
+       + self.bits[2].count_ones() + self.bits[3].count_ones()) as usize
+    }
+
+    /// Create a set of the bytes in either this set or `other`.
+    pub const fn union(self, other: ByteSet) -> ByteSet {
+        ByteSet{
+            bits: [self.bits[0] | other.bits[0], self.bits[1] | other.bits[1],
+                   self.bits[2] | other.bits[2], self.bits[3] | other.bits[3]]
+        }
+    }
+
+    /// Create a set of this set's bytes and `byte`.
+    pub const fn with(mut self, byte: u8) -> ByteSet {
+        self.bits[(byte >> 6) as usize] |= 1 << (byte & 0x3F);
+        self
+    }
+
+    /// Create a set of this set's bytes and `bytes`.
+    pub const fn with_bytes(mut self, bytes: &[u8]) -> ByteSet {
+        let mut n = 0;
+
+        while n < bytes.len() {
+            self = self.with(bytes[n]);
+            n   += 1;
+        }
+
+        self
+    }
+
+    /// Create a set of this set's bytes and the bytes from `start` to `end`, inclusive.
+    pub const fn with_range(mut self, start: u8, end: u8) -> ByteSet {
+        let mut byte = start as usize;
+
+        while byte <= end as usize {
+            self  = self.with(byte as u8);
+            byte += 1;
+        }
+
+        self
+    }
+}
+
+impl BitAnd for ByteSet {
+    type Output = ByteSet;
+
+    fn bitand(self, other: ByteSet) -> ByteSet {
+        self.intersection(other)
+    }
+}
+
+impl BitOr for ByteSet {
+    type Output = ByteSet;
+
+    fn bitor(self, other: ByteSet) -> ByteSet {
+        self.union(other)
+    }
+}
+
+impl Default for ByteSet {
+    fn default() -> ByteSet {
+        ByteSet::EMPTY
+    }
+}
+
+impl fmt::Debug for ByteSet {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Not for ByteSet {
+    type Output = ByteSet;
+
+    fn not(self) -> ByteSet {
+        self.complement()
+    }
+}
+
+impl Sub for ByteSet {
+    type Output = ByteSet;
+
+    fn sub(self, other: ByteSet) -> ByteSet {
+        self.difference(other)
+    }
+}
@@ -0,0 +1,486 @@
+// +-----------------------------------------------------------------------------------------------+
+// | Copyright 2016 Sean Kerr                                                                      |
+// |                                                                                               |
+// | Licensed under the Apache License, Version 2.0 (the "License");                               |
+// | you may not use this file except in compliance with the License.                              |
+// | You may obtain a copy of the License at                                                       |
+// |                                                                                               |
+// |  http://www.apache.org/licenses/LICENSE-2.0                                                   |
+// |                                                                                               |
+// | Unless required by applicable law or agreed to in writing, software                           |
+// | distributed under the License is distributed on an "AS IS" BASIS,                             |
+// | WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.                      |
+// | See the License for the specific language governing permissions and                           |
+// | limitations under the License.                                                                |
+// +-----------------------------------------------------------------------------------------------+
+// | Author: Sean Kerr <sean@metatomic.io>                                                         |
+// +-----------------------------------------------------------------------------------------------+
+
+//! Buffered byte streams.
+
+use ByteContext;
+use std::fmt;
+use std::io::{ self, Read };
+
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+#[cfg(feature = "tokio")]
+use std::task::{ Context, Poll };
+#[cfg(feature = "tokio")]
+use tokio::io::{ AsyncRead, ReadBuf };
+
+/// Byte stream that's fed successive chunks of data, such as network reads.
+///
+/// Each chunk is appended to an internal buffer, so that a token straddling two chunks can still
+/// be collected and sliced as a single contiguous slice. When a chunk is pushed, the bytes before
+/// the mark index (or the stream index, when it's behind the mark) are released, so that only the
+/// bytes from the oldest live mark onward are retained. Marking the start of each token with
+/// `bs_mark!()` keeps the buffer bounded.
+///
+/// All indices are relative to the retained bytes, and shift back by the amount of bytes released
+/// each time a chunk is pushed. `offset()` retrieves the absolute offset of the first retained
+/// byte.
+#[derive(Clone, Default)]
+pub struct ChunkedStream {
+    /// Current byte.
+    pub byte: u8,
+
+    /// Retained bytes.
+    buffer: Vec<u8>,
+
+    /// Mark index.
+    pub mark_index: usize,
+
+    /// Absolute offset of the first retained byte.
+    offset: usize,
+
+    /// Stream index.
+    pub stream_index: usize
+}
+
+impl ChunkedStream {
+    /// Create a new `ChunkedStream`.
+    pub fn new() -> ChunkedStream {
+        ChunkedStream::default()
+    }
+
+    /// Create a new `ChunkedStream` whose buffer has room for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> ChunkedStream {
+        ChunkedStream{
+            buffer: Vec::with_capacity(capacity),
+            ..ChunkedStream::default()
+        }
+    }
+
+    /// Retrieve the absolute offset of the first retained byte.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Release the bytes before the mark index, or before the stream index when it's behind the
+    /// mark, and append `chunk`.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.release();
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// Release the bytes before the mark index, or before the stream index when it's behind the
+    /// mark.
+    fn release(&mut self) {
+        let release = self.mark_index.min(self.stream_index);
+
+        if release > 0 {
+            self.buffer.drain(..release);
+
+            self.mark_index   -= release;
+            self.offset       += release;
+            self.stream_index -= release;
+        }
+    }
+}
+
+impl ByteContext for ChunkedStream {
+    #[inline]
+    fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    #[inline]
+    fn mark_index(&self) -> usize {
+        self.mark_index
+    }
+
+    #[inline]
+    fn set_byte(&mut self, byte: u8) {
+        self.byte = byte;
+    }
+
+    #[inline]
+    fn set_mark_index(&mut self, index: usize) {
+        self.mark_index = index;
+    }
+
+    #[inline]
+    fn set_stream_index(&mut self, index: usize) {
+        self.stream_index = index;
+    }
+
+    #[inline]
+    fn stream(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    #[inline]
+    fn stream_index(&self) -> usize {
+        self.stream_index
+    }
+}
+
+impl fmt::Debug for ChunkedStream {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ChunkedStream({})", self)
+    }
+}
+
+impl fmt::Display for ChunkedStream {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if is_visible_8bit!(self.byte) || self.byte == 0x20 || self.byte == 0xFF {
+            write!(formatter, "byte[{}]='{}', mark_index={}, offset={}, stream_index={}",
+                   self.byte, self.byte as char, self.mark_index, self.offset, self.stream_index)
+        } else {
+            write!(formatter, "byte[{}]='', mark_index={}, offset={}, stream_index={}",
+                   self.byte, self.mark_index, self.offset, self.stream_index)
+        }
+    }
+}
+
+/// Default amount of bytes to read at a time.
+const READ_SIZE: usize = 8192;
+
+/// Byte stream adapter over a tokio `AsyncRead` implementation, such as a socket.
+///
+/// The macros operate on `stream`, a `ChunkedStream` holding the bytes read so far. When a
+/// collector locates end-of-stream, awaiting `fill()` reads more bytes into it, after which
+/// collection can continue where it left off. Like `ChunkedStream::push()`, filling releases the
+/// bytes before the mark index, so indices shift back by the amount of bytes released.
+///
+/// Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+pub struct AsyncStream<R: AsyncRead + Unpin> {
+    /// Indicates that the reader has no more bytes.
+    finished: bool,
+
+    /// Amount of bytes to read at a time.
+    read_size: usize,
+
+    /// Reader.
+    reader: R,
+
+    /// Bytes read so far.
+    pub stream: ChunkedStream
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin> AsyncStream<R> {
+    /// Create a new `AsyncStream` over `reader`.
+    pub fn new(reader: R) -> AsyncStream<R> {
+        AsyncStream::with_read_size(reader, READ_SIZE)
+    }
+
+    /// Create a new `AsyncStream` over `reader`, reading up to `read_size` bytes at a time.
+    pub fn with_read_size(reader: R, read_size: usize) -> AsyncStream<R> {
+        AsyncStream{
+            finished:  false,
+            read_size: read_size.max(1),
+            reader,
+            stream:    ChunkedStream::with_capacity(read_size)
+        }
+    }
+
+    /// Read more bytes into `stream`.
+    ///
+    /// The returned future yields `false` once the reader has no more bytes.
+    pub fn fill(&mut self) -> Fill<'_, R> {
+        Fill{
+            input: self
+        }
+    }
+
+    /// Release the reader, discarding the retained bytes.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Indicates that the reader has no more bytes.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Future returned by `AsyncStream::fill()`.
+#[cfg(feature = "tokio")]
+pub struct Fill<'a, R: AsyncRead + Unpin> {
+    /// Stream being filled.
+    input: &'a mut AsyncStream<R>
+}
+
+#[cfg(feature = "tokio")]
+impl<'a, R: AsyncRead + Unpin> Future for Fill<'a, R> {
+    type Output = io::Result<bool>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<bool>> {
+        let input = &mut *self.input;
+
+        if input.finished {
+            return Poll::Ready(Ok(false));
+        }
+
+        input.stream.release();
+
+        let length = input.stream.buffer.len();
+
+        input.stream.buffer.resize(length + input.read_size, 0);
+
+        let mut buffer = ReadBuf::new(&mut input.stream.buffer[length..]);
+        let poll       = Pin::new(&mut input.reader).poll_read(context, &mut buffer);
+        let filled     = buffer.filled().len();
+
+        input.stream.buffer.truncate(length + filled);
+
+        match poll {
+            Poll::Ready(Ok(())) => {
+                input.finished = filled == 0;
+
+                Poll::Ready(Ok(filled > 0))
+            },
+            Poll::Ready(Err(error)) => {
+                input.finished = true;
+
+                Poll::Ready(Err(error))
+            },
+            Poll::Pending => {
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Byte stream backed by a `Read` implementation, such as a file, a socket, or any `BufRead`.
+///
+/// Bytes are read into an internal buffer as the collectors locate end-of-stream, so that inputs
+/// of any size can be parsed. Bytes are never released automatically: the buffer only stays
+/// bounded when `release()` is called between tokens, which releases the bytes before the mark
+/// index. Marking the start of each token with `bs_mark!()` and then calling `release()` retains
+/// only the bytes from the mark onward.
+///
+/// All indices are relative to the retained bytes, and shift back by the amount of bytes released
+/// each time bytes are released. `offset()` retrieves the absolute offset of the first retained
+/// byte. Neither marking nor filling releases bytes, so indices are stable while collecting.
+///
+/// ```
+/// use byte_slice::prelude::*;
+/// use byte_slice::{ Collect, ReadStream };
+///
+/// let input      = "1 2 3 ".repeat(1000);
+/// let mut stream = ReadStream::with_read_size(input.as_bytes(), 64);
+/// let mut sum    = 0;
+///
+/// loop {
+///     stream.mark();
+///     stream.release();
+///
+///     match stream.collect_digits::<u32>() {
+///         Collect::Done(value) => sum += value,
+///         _                    => break
+///     }
+///
+///     assert!(stream.stream().len() <= 128);
+/// }
+///
+/// assert_eq!(sum, 6000);
+/// ```
+///
+/// The stream starts out empty. Read errors end the stream, and can be retrieved with
+/// `take_error()`.
+pub struct ReadStream<R: Read> {
+    /// Current byte.
+    pub byte: u8,
+
+    /// Retained bytes.
+    buffer: Vec<u8>,
+
+    /// Read error that ended the stream.
+    error: Option<io::Error>,
+
+    /// Indicates that the reader has no more bytes.
+    finished: bool,
+
+    /// Mark index.
+    pub mark_index: usize,
+
+    /// Absolute offset of the first retained byte.
+    offset: usize,
+
+    /// Amount of bytes to read at a time.
+    read_size: usize,
+
+    /// Reader.
+    reader: R,
+
+    /// Stream index.
+    pub stream_index: usize
+}
+
+impl<R: Read> ReadStream<R> {
+    /// Create a new `ReadStream` over `reader`.
+    pub fn new(reader: R) -> ReadStream<R> {
+        ReadStream::with_read_size(reader, READ_SIZE)
+    }
+
+    /// Create a new `ReadStream` over `reader`, reading `read_size` bytes at a time.
+    pub fn with_read_size(reader: R, read_size: usize) -> ReadStream<R> {
+        ReadStream{
+            byte:         0,
+            buffer:       Vec::with_capacity(read_size),
+            error:        None,
+            finished:     false,
+            mark_index:   0,
+            offset:       0,
+            read_size:    read_size.max(1),
+            reader,
+            stream_index: 0
+        }
+    }
+
+    /// Indicates that the reader has no more bytes.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Retrieve the absolute offset of the first retained byte.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Release the bytes before the mark index, or before the stream index when it's behind the
+    /// mark, once they make up at least half of the buffer.
+    ///
+    /// Releasing is deferred until then, so that calling this once per token moves each retained
+    /// byte a bounded amount of times.
+    pub fn release(&mut self) {
+        let release = self.mark_index.min(self.stream_index);
+
+        if release > 0 && release * 2 >= self.buffer.len() {
+            self.buffer.drain(..release);
+
+            self.mark_index   -= release;
+            self.offset       += release;
+            self.stream_index -= release;
+        }
+    }
+
+    /// Retrieve the read error that ended the stream, if any.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Release the reader, discarding the retained bytes.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> ByteContext for ReadStream<R> {
+    #[inline]
+    fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    fn fill(&mut self) -> bool {
+        let length = self.buffer.len();
+
+        if self.finished {
+            return false;
+        }
+
+        self.buffer.resize(length + self.read_size, 0);
+
+        loop {
+            match self.reader.read(&mut self.buffer[length..]) {
+                Ok(0) => {
+                    self.finished = true;
+                },
+                Ok(n) => {
+                    self.buffer.truncate(length + n);
+
+                    return true;
+                },
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                },
+                Err(error) => {
+                    self.error    = Some(error);
+                    self.finished = true;
+                }
+            }
+
+            self.buffer.truncate(length);
+
+            return false;
+        }
+    }
+
+    #[inline]
+    fn mark_index(&self) -> usize {
+        self.mark_index
+    }
+
+    #[inline]
+    fn set_byte(&mut self, byte: u8) {
+        self.byte = byte;
+    }
+
+    #[inline]
+    fn set_mark_index(&mut self, index: usize) {
+        self.mark_index = index;
+    }
+
+    #[inline]
+    fn set_stream_index(&mut self, index: usize) {
+        self.stream_index = index;
+    }
+
+    #[inline]
+    fn stream(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    #[inline]
+    fn stream_index(&self) -> usize {
+        self.stream_index
+    }
+}
+
+impl<R: Read> fmt::Debug for ReadStream<R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ReadStream({})", self)
+    }
+}
+
+impl<R: Read> fmt::Display for ReadStream<R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if is_visible_8bit!(self.byte) || self.byte == 0x20 || self.byte == 0xFF {
+            write!(formatter, "byte[{}]='{}', mark_index={}, offset={}, stream_index={}",
+                   self.byte, self.byte as char, self.mark_index, self.offset, self.stream_index)
+        } else {
+            write!(formatter, "byte[{}]='', mark_index={}, offset={}, stream_index={}",
+                   self.byte, self.mark_index, self.offset, self.stream_index)
+        }
+    }
+}
@@ -16,7 +16,73 @@
 // | Author: Sean Kerr <sean@metatomic.io>                                                         |
 // +-----------------------------------------------------------------------------------------------+
 
-use ByteStream;
+#![allow(clippy::manual_range_contains, clippy::redundant_pattern_matching, unused_assignments)]
+
+use classes;
+use std::io::{ self, Read };
+use { ByteContext, ByteContextMut, ByteSet, ByteStream, ByteStreamMut, ChunkedStream, Collect,
+      DigitPolicy, Finder, FinderRev, FloatCollect, FloatFormat, Keywords, Match, MatchKind,
+      MultiFinder, NumberState, ParseResult, ReadStream };
+
+struct Context<'a> {
+    byte:         u8,
+    mark_index:   usize,
+    stream:       &'a [u8],
+    stream_index: usize
+}
+
+impl<'a> ByteContext for Context<'a> {
+    fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    fn mark_index(&self) -> usize {
+        self.mark_index
+    }
+
+    fn set_byte(&mut self, byte: u8) {
+        self.byte = byte;
+    }
+
+    fn set_mark_index(&mut self, index: usize) {
+        self.mark_index = index;
+    }
+
+    fn set_stream_index(&mut self, index: usize) {
+        self.stream_index = index;
+    }
+
+    fn stream(&self) -> &[u8] {
+        self.stream
+    }
+
+    fn stream_index(&self) -> usize {
+        self.stream_index
+    }
+}
+
+/// Reader that yields at most `step` bytes at a time, then an error if `error` is set.
+struct StepReader<'a> {
+    data:  &'a [u8],
+    error: bool,
+    step:  usize
+}
+
+impl<'a> Read for StepReader<'a> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if self.data.is_empty() && self.error {
+            return Err(io::Error::other("step"));
+        }
+
+        let length = self.step.min(buffer.len()).min(self.data.len());
+
+        buffer[..length].copy_from_slice(&self.data[..length]);
+
+        self.data = &self.data[length..];
+
+        Ok(length)
+    }
+}
 
 #[test]
 fn collect_eos() {
@@ -63,6 +129,228 @@ fn collect_word() {
     assert_eq!(bs_slice_ignore!(c), b"stream");
 }
 
+#[test]
+fn collect_context() {
+    let mut c = Context{
+        byte:         0,
+        mark_index:   0,
+        stream:       b"stream 42",
+        stream_index: 0
+    };
+    let mut v = 0;
+
+    bs_collect_until!(c, c.byte() == b' ', panic!());
+
+    assert_eq!(bs_slice_ignore!(c), b"stream");
+
+    bs_mark!(c);
+    bs_collect_digits8!(c, v, panic!(), break);
+
+    assert_eq!(v, 42);
+    assert_eq!(c.byte(), b'2');
+    assert_eq!(bs_slice!(c), b"42");
+    assert!(bs_is_eos!(c));
+}
+
+#[test]
+fn collect_bounded_exact() {
+    let mut c = ByteStream::new(b"200 OK");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u16, 10, DigitPolicy::exact(3), panic!(), panic!(), panic!());
+
+    assert_eq!(v, 200);
+    assert_eq!(c.byte, b' ');
+
+    let mut c = ByteStream::new(b"20 OK");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u16, 10, DigitPolicy::exact(3), panic!(), break, panic!());
+
+    assert_eq!(v, 20);
+    assert_eq!(c.byte, b' ');
+
+    let mut c = ByteStream::new(b"2000 OK");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u16, 10, DigitPolicy::exact(3), panic!(), break, panic!());
+
+    assert_eq!(v, 200);
+    assert_eq!(bs_index!(c), 4);
+}
+
+#[test]
+fn collect_bounded_max() {
+    let mut c = ByteStream::new(b"fffffffff\r\n");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u64, 16, DigitPolicy::max(8), panic!(), break, panic!());
+
+    assert_eq!(v, 0xFFFFFFFF);
+    assert_eq!(bs_index!(c), 9);
+
+    let mut c = ByteStream::new(b"0000000001;");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u8, 10, DigitPolicy::UNBOUNDED, panic!(), panic!(), panic!());
+
+    assert_eq!(v, 1);
+}
+
+#[test]
+fn collect_bounded_leading_zeros() {
+    let policy = DigitPolicy{
+        leading_zeros: false,
+        ..DigitPolicy::UNBOUNDED
+    };
+
+    let mut c = ByteStream::new(b"01");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u8, 10, policy, panic!(), break, panic!());
+
+    assert_eq!(v, 0);
+    assert_eq!(c.byte, b'1');
+
+    let mut c = ByteStream::new(b"0;");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u8, 10, policy, panic!(), panic!(), panic!());
+
+    assert_eq!(v, 0);
+    assert_eq!(c.byte, b';');
+
+    let mut c = ByteStream::new(b"10");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u8, 10, policy, panic!(), panic!(), break);
+
+    assert_eq!(v, 10);
+}
+
+#[test]
+fn collect_bounded_overflow() {
+    let mut c = ByteStream::new(b"256");
+    let mut v = 0;
+
+    bs_collect_bounded!(c, v, u8, 10, DigitPolicy::exact(3), break, panic!(), panic!());
+
+    assert_eq!(v, 25);
+}
+
+#[test]
+fn collect_digits_eight() {
+    let streams: &[&[u8]] = &[
+        b"12345678", b"123456789", b"1234567x9", b"00000000000000000001", b"18446744073709551615",
+        b"18446744073709551616", b"99999999999999999999", b"4294967295 ", b"4294967296 ",
+        b"12345678/", b"12345678:", b"1234567\xFA00000000", b"/0000000"
+    ];
+
+    for stream in streams.iter() {
+        let mut a = ByteStream::new(stream);
+        let mut b = ByteStream::new(stream);
+        let mut v = 0u64;
+        let mut w = 0u64;
+        let mut x = 0u32;
+        let mut y = 0u32;
+
+        bs_collect_digits64!(a, v, break, break);
+        bs_collect_digits64!(b, w, {}, break, break);
+
+        assert_eq!(v, w);
+        assert_eq!(bs_index!(a), bs_index!(b));
+        assert_eq!(a.byte, b.byte);
+
+        let mut a = ByteStream::new(stream);
+        let mut b = ByteStream::new(stream);
+
+        bs_collect_digits32!(a, x, break, break);
+        bs_collect_digits32!(b, y, {}, break, break);
+
+        assert_eq!(x, y);
+        assert_eq!(bs_index!(a), bs_index!(b));
+        assert_eq!(a.byte, b.byte);
+
+        let mut a = ByteStream::new(stream);
+        let mut b = ByteStream::new(stream);
+        let mut z = 0u64;
+
+        bs_collect_digits64!(b, z, {}, break, break);
+
+        match a.collect_digits::<u64>() {
+            Collect::Done(value) | Collect::Eos(value) | Collect::Overflow(value) => {
+                assert_eq!(value, z);
+            },
+            Collect::Violation(_) => panic!()
+        }
+
+        assert_eq!(bs_index!(a), bs_index!(b));
+        assert_eq!(a.byte, b.byte);
+    }
+}
+
+#[test]
+fn collect_digits_eight_test() {
+    for position in 0..8 {
+        for n in 0..=255u8 {
+            let mut a = *b"12345678";
+
+            a[position] = n;
+
+            if is_digit!(n) {
+                let expected = String::from_utf8(a.to_vec()).unwrap().parse::<u32>().unwrap();
+
+                assert_eq!(::number::eight_digits(&a), Some(expected));
+            } else {
+                assert_eq!(::number::eight_digits(&a), None);
+            }
+        }
+    }
+}
+
+#[test]
+fn collect_digits_u128() {
+    let mut c = ByteStream::new(b"340282366920938463463374607431768211455 ");
+    let mut v = 0;
+
+    bs_collect_digits!(c, v, u128, panic!(), panic!());
+
+    assert_eq!(v, u128::MAX);
+    assert_eq!(c.byte, b' ');
+}
+
+#[test]
+fn collect_digits_u128_overflow() {
+    let mut c = ByteStream::new(b"340282366920938463463374607431768211456");
+    let mut v = 0;
+
+    bs_collect_digits!(c, v, u128, break, panic!());
+
+    assert_eq!(v, 34028236692093846346337460743176821145);
+    assert_eq!(c.byte, b'6');
+}
+
+#[test]
+fn collect_digits_usize() {
+    let mut c = ByteStream::new(b"1024");
+    let mut v: usize = 0;
+
+    bs_collect_digits!(c, v, usize, panic!(), break);
+
+    assert_eq!(v, 1024);
+}
+
+#[test]
+fn collect_digits_cast() {
+    let mut c = ByteStream::new(b"300;");
+    let mut v: u32 = 0;
+
+    bs_collect_digits!(c, v, u8, {}, break, panic!(), u32);
+
+    assert_eq!(v, 30);
+    assert_eq!(c.byte, b'0');
+}
+
 #[test]
 fn collect_digits8_eos() {
     let mut c = ByteStream::new(b"42");
@@ -260,6 +548,37 @@ fn collect_digits64_max() {
     );
 }
 
+#[test]
+fn collect_digits_max_value() {
+    let mut c = ByteStream::new(b"42");
+    let mut v = 0u8;
+
+    bs_collect_digits8!(c, v, break, if v > 41 { break; }, { panic!(); });
+
+    assert_eq!(v, 4);
+
+    let mut c = ByteStream::new(b"42");
+    let mut v = 0u16;
+
+    bs_collect_digits16!(c, v, break, if v > 41 { break; }, { panic!(); });
+
+    assert_eq!(v, 4);
+
+    let mut c = ByteStream::new(b"42");
+    let mut v = 0u32;
+
+    bs_collect_digits32!(c, v, break, if v > 41 { break; }, { panic!(); });
+
+    assert_eq!(v, 4);
+
+    let mut c = ByteStream::new(b"42");
+    let mut v = 0u64;
+
+    bs_collect_digits64!(c, v, break, if v > 41 { break; }, { panic!(); });
+
+    assert_eq!(v, 4);
+}
+
 #[test]
 fn collect_digits64_stop() {
     let mut c = ByteStream::new(b"42stop");
@@ -291,6 +610,149 @@ fn collect_digits64_test() {
     }
 }
 
+#[test]
+fn collect_float() {
+    let mut c = ByteStream::new(b"q=0.8;");
+    let mut v = 0.0;
+
+    bs_jump!(c, 2);
+    bs_collect_float!(c, v, f64, panic!(), break);
+
+    assert_eq!(v, 0.8);
+    assert_eq!(c.byte, b';');
+}
+
+#[test]
+fn collect_float_exponent() {
+    let mut c = ByteStream::new(b"-1.5e-3 ");
+    let mut v = 0.0;
+
+    bs_collect_float!(c, v, f32, panic!(), break);
+
+    assert_eq!(v, -1.5e-3);
+    assert_eq!(c.byte, b' ');
+}
+
+#[test]
+fn collect_float_eos() {
+    let mut c = ByteStream::new(b"-0.0");
+    let mut v = 1.0f64;
+
+    bs_collect_float!(c, v, f64, panic!(), break);
+
+    assert_eq!(v, 0.0);
+    assert!(v.is_sign_negative());
+    assert_eq!(c.byte, b'0');
+    assert!(bs_is_eos!(c));
+}
+
+#[test]
+fn collect_float_eos_incomplete() {
+    let mut c = ByteStream::new(b"1.5e");
+    let mut v = 0.0;
+
+    bs_collect_float!(c, v, f64, panic!(), break);
+
+    assert_eq!(v, 0.0);
+    assert_eq!(c.byte, b'e');
+}
+
+#[test]
+fn collect_float_rounding() {
+    let mut c = ByteStream::new(b"0.1000000000000000055511151231257827021181583404541015625;");
+    let mut v = 0.0;
+
+    bs_collect_float!(c, v, f64, panic!(), break);
+
+    assert_eq!(v, 0.1);
+}
+
+#[test]
+fn collect_float_strict() {
+    for stream in [&b".5;"[..], b"5.;", b"inf;", b"nan;", b"1e;", b"-;"].iter() {
+        let mut c = ByteStream::new(stream);
+        let mut v = 0.0;
+
+        bs_collect_float!(c, v, f64, break, panic!());
+
+        assert_eq!(v, 0.0);
+    }
+}
+
+#[test]
+fn collect_float_relaxed() {
+    let mut c = ByteStream::new(b".5;");
+    let mut v = 0.0;
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, panic!(), break);
+
+    assert_eq!(v, 0.5);
+
+    let mut c = ByteStream::new(b"5.;");
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, panic!(), break);
+
+    assert_eq!(v, 5.0);
+
+    let mut c = ByteStream::new(b"-Infinity;");
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, panic!(), break);
+
+    assert_eq!(v, f64::NEG_INFINITY);
+
+    let mut c = ByteStream::new(b"inf;");
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, panic!(), break);
+
+    assert_eq!(v, f64::INFINITY);
+
+    let mut c = ByteStream::new(b"NaN;");
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, panic!(), break);
+
+    assert!(v.is_nan());
+    assert_eq!(c.byte, b';');
+}
+
+#[test]
+fn collect_float_invalid() {
+    let mut c = ByteStream::new(b"1.5e+x");
+    let mut v = 0.0;
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, break, panic!());
+
+    assert_eq!(v, 0.0);
+    assert_eq!(c.byte, b'x');
+
+    let mut c = ByteStream::new(b"infin;");
+
+    bs_collect_float!(c, v, f64, FloatFormat::RELAXED, break, panic!());
+
+    assert_eq!(v, 0.0);
+    assert_eq!(c.byte, b';');
+}
+
+#[test]
+fn collect_hex_u128() {
+    let mut c = ByteStream::new(b"ffffffffffffffffffffffffffffffffQ");
+    let mut v = 0;
+
+    bs_collect_hex!(c, v, u128, panic!(), panic!());
+
+    assert_eq!(v, u128::MAX);
+    assert_eq!(c.byte, b'Q');
+}
+
+#[test]
+fn collect_hex_usize() {
+    let mut c = ByteStream::new(b"1A2b");
+    let mut v: usize = 0;
+
+    bs_collect_hex!(c, v, usize, panic!(), break);
+
+    assert_eq!(v, 0x1A2B);
+}
+
 #[test]
 fn collect_hex8() {
     let mut c = ByteStream::new(b"FF");
@@ -508,44 +970,223 @@ fn collect_hex64_test() {
 }
 
 #[test]
-fn collect_length() {
-    let mut c = ByteStream::new(b"stream data");
+fn collect_prefixed() {
+    for &(stream, value) in [(&b"0x1F;"[..], 0x1F), (b"0X1f;", 0x1F), (b"0o755;", 0o755),
+                             (b"0b1010;", 0b1010), (b"0755;", 755), (b"42;", 42),
+                             (b"0;", 0)].iter() {
+        let mut c = ByteStream::new(stream);
+        let mut v = 0;
 
-    bs_collect_length!(c, 11);
+        bs_collect_prefixed!(c, v, u32, panic!(), break);
 
-    assert_eq!(bs_slice!(c), b"stream data");
+        assert_eq!(v, value);
+        assert_eq!(c.byte, b';');
+    }
 }
 
 #[test]
-fn collect_length_check() {
-    let mut c = ByteStream::new(b"stream data");
+fn collect_prefixed_stop() {
+    let mut c = ByteStream::new(b"0b102");
+    let mut v = 0;
 
-    bs_collect_length!(c, 11,
-        if c.byte == b' ' {
-            break;
-        }, {
-            panic!();
-        }
-    );
+    bs_collect_prefixed!(c, v, u8, panic!(), panic!());
 
-    assert_eq!(bs_slice!(c), b"stream ");
-    assert_eq!(bs_slice_ignore!(c), b"stream");
-}
+    assert_eq!(v, 0b10);
+    assert_eq!(c.byte, b'2');
 
-#[test]
-fn collect_when() {
-    let mut c = ByteStream::new(b"stream data");
+    let mut c = ByteStream::new(b"10x");
+    let mut v = 0;
 
-    bs_collect_when!(c,
-        is_alpha!(c.byte),
-        {
-            break;
-        }
-    );
+    bs_collect_prefixed!(c, v, u8, panic!(), panic!());
 
-    assert_eq!(c.byte, b' ');
-    assert_eq!(bs_slice!(c), b"stream ");
-    assert_eq!(bs_slice_ignore!(c), b"stream");
+    assert_eq!(v, 10);
+    assert_eq!(c.byte, b'x');
+}
+
+#[test]
+fn collect_prefixed_overflow() {
+    let mut c = ByteStream::new(b"0x100");
+    let mut v = 0;
+
+    bs_collect_prefixed!(c, v, u8, break, panic!());
+
+    assert_eq!(v, 0x10);
+}
+
+#[test]
+fn collect_radix() {
+    let mut c = ByteStream::new(b"0644 ");
+    let mut v = 0;
+
+    bs_collect_radix!(c, v, u16, 8, panic!(), break);
+
+    assert_eq!(v, 0o644);
+    assert_eq!(c.byte, b' ');
+
+    let mut c = ByteStream::new(b"11110000");
+    let mut v = 0;
+
+    bs_collect_radix!(c, v, u8, 2, panic!(), break);
+
+    assert_eq!(v, 0xF0);
+
+    let mut c = ByteStream::new(b"Zz9-");
+    let mut v = 0;
+
+    bs_collect_radix!(c, v, u64, 36, panic!(), break);
+
+    assert_eq!(v, 35 * 36 * 36 + 35 * 36 + 9);
+    assert_eq!(c.byte, b'-');
+}
+
+#[test]
+fn collect_radix_overflow() {
+    let mut c = ByteStream::new(b"111111111");
+    let mut v = 0;
+
+    bs_collect_radix!(c, v, u8, 2, break, panic!());
+
+    assert_eq!(v, 0xFF);
+    assert_eq!(bs_index!(c), 9);
+}
+
+#[test]
+fn collect_radix_test() {
+    for radix in 2..37u8 {
+        for n in 0..255u8 {
+            let a     = &[n];
+            let mut c = ByteStream::new(a);
+            let mut v = 0;
+
+            bs_collect_radix!(c, v, u8, radix, panic!(), break);
+
+            if (n as char).is_digit(radix as u32) {
+                assert!(bs_is_eos!(c));
+            } else {
+                assert_eq!(v, 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn collect_signed_negative() {
+    let mut c = ByteStream::new(b"-42 ");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i32, panic!(), panic!());
+
+    assert_eq!(v, -42);
+    assert_eq!(c.byte, b' ');
+}
+
+#[test]
+fn collect_signed_positive() {
+    let mut c = ByteStream::new(b"+17");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i32, panic!(), break);
+
+    assert_eq!(v, 17);
+}
+
+#[test]
+fn collect_signed_unsigned() {
+    let mut c = ByteStream::new(b"17-");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i8, panic!(), panic!());
+
+    assert_eq!(v, 17);
+    assert_eq!(c.byte, b'-');
+}
+
+#[test]
+fn collect_signed_min() {
+    let mut c = ByteStream::new(b"-9223372036854775808");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i64, panic!(), break);
+
+    assert_eq!(v, i64::MIN);
+
+    let mut c = ByteStream::new(b"-128");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i8, panic!(), break);
+
+    assert_eq!(v, i8::MIN);
+}
+
+#[test]
+fn collect_signed_overflow() {
+    let mut c = ByteStream::new(b"128");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i8, break, panic!());
+
+    assert_eq!(v, 12);
+    assert_eq!(c.byte, b'8');
+
+    let mut c = ByteStream::new(b"-129");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i8, break, panic!());
+
+    assert_eq!(v, -12);
+    assert_eq!(c.byte, b'9');
+}
+
+#[test]
+fn collect_signed_sign_eos() {
+    let mut c = ByteStream::new(b"-");
+    let mut v = 0;
+
+    bs_collect_signed!(c, v, i128, panic!(), break);
+
+    assert_eq!(v, 0);
+    assert!(bs_is_eos!(c));
+}
+
+#[test]
+fn collect_length() {
+    let mut c = ByteStream::new(b"stream data");
+
+    bs_collect_length!(c, 11);
+
+    assert_eq!(bs_slice!(c), b"stream data");
+}
+
+#[test]
+fn collect_length_check() {
+    let mut c = ByteStream::new(b"stream data");
+
+    bs_collect_length!(c, 11,
+        if c.byte == b' ' {
+            break;
+        }, {
+            panic!();
+        }
+    );
+
+    assert_eq!(bs_slice!(c), b"stream ");
+    assert_eq!(bs_slice_ignore!(c), b"stream");
+}
+
+#[test]
+fn collect_when() {
+    let mut c = ByteStream::new(b"stream data");
+
+    bs_collect_when!(c,
+        is_alpha!(c.byte),
+        {
+            break;
+        }
+    );
+
+    assert_eq!(c.byte, b' ');
+    assert_eq!(bs_slice!(c), b"stream ");
+    assert_eq!(bs_slice_ignore!(c), b"stream");
 }
 
 #[test]
@@ -609,6 +1250,55 @@ fn count_when() {
     assert_eq!(c.byte, b'f');
 }
 
+#[test]
+fn count_offset() {
+    let mut c = ByteStream::new(b"aaaaaa");
+
+    bs_jump!(c, 4);
+
+    assert_eq!(bs_count!(c, b'a'), 2);
+    assert_eq!(bs_count_when!(c, c.byte() == b'a'), 2);
+    assert_eq!(c.count_when(|c| c.byte() == b'a'), 2);
+    assert_eq!(c.byte(), b'a');
+
+    bs_jump!(c, 2);
+
+    assert_eq!(bs_count_when!(c, c.byte() == b'a'), 0);
+}
+
+#[test]
+fn count_when_path() {
+    struct Parser<'a> {
+        context: ByteStream<'a>
+    }
+
+    let mut p = Parser{ context: ByteStream::new(b"fancy stream data") };
+
+    assert_eq!(bs_count_when!(p.context, p.context.byte == b'a'), 4);
+    assert_eq!(p.context.byte, b'f');
+}
+
+#[test]
+#[should_panic]
+fn next_eos() {
+    let mut c = ByteStream::new(b"");
+
+    bs_next!(c);
+}
+
+#[test]
+fn next_unchecked() {
+    let mut c = ByteStream::new(b"ab");
+
+    unsafe {
+        c.next_unchecked();
+        c.next_unchecked();
+    }
+
+    assert_eq!(c.byte(), b'b');
+    assert!(bs_is_eos!(c));
+}
+
 #[test]
 fn find1() {
     let c = ByteStream::new(b"stream data");
@@ -619,6 +1309,49 @@ fn find1() {
     }
 }
 
+#[test]
+fn find_long() {
+    for length in 0..100 {
+        for position in 0..length + 1 {
+            let mut a = vec![b'a'; length];
+
+            if position < length {
+                a[position] = b'\n';
+            }
+
+            let c        = ByteStream::new(&a);
+            let expected = if position < length { Some(position) } else { None };
+
+            assert_eq!(bs_find!(c, b'\n'), expected);
+            assert_eq!(::search::find_swar(&a, [b'\n']), expected);
+
+            for start in 0..length + 1 {
+                let expected = if start <= position && position < length {
+                    Some(position)
+                } else {
+                    None
+                };
+
+                assert_eq!(bs_find!(c, start, b'\n'), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn find_high_bytes() {
+    let mut a = vec![0xFF; 70];
+
+    a[65] = 0x80;
+
+    let c = ByteStream::new(&a);
+
+    assert_eq!(bs_find!(c, 0x80), Some(65));
+    assert_eq!(bs_find!(c, 0x7F), None);
+    assert_eq!(::search::find_swar(&a, [0x80]), Some(65));
+    assert_eq!(::search::find_swar(&a, [0x7F, 0x00]), None);
+}
+
 #[test]
 fn find2() {
     let c = ByteStream::new(b"");
@@ -649,6 +1382,228 @@ fn find4() {
     }
 }
 
+#[test]
+fn find_any2() {
+    let c = ByteStream::new(b"header-name: value; param=\"quoted\"\r\n");
+
+    assert_eq!(bs_find2!(c, b':', b';'), Some(11));
+    assert_eq!(bs_find2!(c, 12, b':', b';'), Some(18));
+    assert_eq!(bs_find2!(c, 19, b':', b';'), None);
+    assert_eq!(bs_find2!(c, 100, b':', b';'), None);
+}
+
+#[test]
+fn find_any3() {
+    let c = ByteStream::new(b"header-name: value; param=\"quoted\"\r\n");
+
+    assert_eq!(bs_find3!(c, b'"', b'=', b'\r'), Some(25));
+    assert_eq!(bs_find3!(c, 26, b'"', b'=', b'\r'), Some(26));
+    assert_eq!(bs_find3!(c, 34, b'"', b'=', b'\r'), Some(34));
+    assert_eq!(bs_find3!(c, b'!', b'#', b'$'), None);
+}
+
+#[test]
+fn find_any_long() {
+    for length in 0..100 {
+        for position in 0..length {
+            for needle in [b'x', b'y', b'z'].iter() {
+                let mut a = vec![b'a'; length];
+
+                a[position] = *needle;
+
+                if position + 3 < length {
+                    a[position + 3] = b'x';
+                }
+
+                let c = ByteStream::new(&a);
+
+                assert_eq!(bs_find3!(c, b'x', b'y', b'z'), Some(position));
+                assert_eq!(::search::find_swar(&a, [b'x', b'y', b'z']), Some(position));
+
+                if *needle != b'z' {
+                    assert_eq!(bs_find2!(c, b'x', b'y'), Some(position));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn find_with() {
+    let finder = Finder::new(b"\r\n--boundary");
+    let a      = ByteStream::new(b"preamble\r\n--boundary\r\npart\r\n--boundary--");
+    let b      = ByteStream::new(b"\r\n--boundar");
+
+    assert_eq!(bs_find_with!(a, finder), Some(8));
+    assert_eq!(bs_find_with!(a, 9, finder), Some(26));
+    assert_eq!(bs_find_with!(a, 27, finder), None);
+    assert_eq!(bs_find_with!(b, finder), None);
+}
+
+#[test]
+fn find_with_owned() {
+    let finder = {
+        let pattern = b"needle".to_vec();
+
+        Finder::new(&pattern).into_owned()
+    };
+
+    assert_eq!(finder.pattern(), b"needle");
+    assert_eq!(finder.find(b"haystack needle haystack"), Some(9));
+    assert_eq!(finder.find(b""), None);
+    assert_eq!(Finder::new(b"").find(b"haystack"), None);
+}
+
+#[test]
+fn find_with_naive() {
+    let mut boundary = vec![b'a'; 69];
+    let mut run      = vec![b'a'; 200];
+
+    boundary.push(b'b');
+    run.extend_from_slice(&boundary);
+
+    let haystacks: &[&[u8]] = &[
+        b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab",
+        b"abababababababababababababababababababababababababababababababaabab",
+        b"----------------------------------------------------------------X--",
+        b"the quick brown fox jumps over the lazy dog, the quick brown fox!",
+        &run
+    ];
+    let patterns: &[&[u8]] = &[
+        b"a", b"b", b"ab", b"aab", b"aaab", b"abab", b"baab", b"X", b"-X-", b"---X",
+        b"fox!", b"the", b"dog,", b"lazy dog", b"quick brown fox jumps", b"cat", &boundary
+    ];
+
+    for haystack in haystacks.iter() {
+        for pattern in patterns.iter() {
+            let finder = Finder::new(pattern);
+            let c      = ByteStream::new(haystack);
+
+            for start in 0..haystack.len() {
+                let expected = haystack[start..].windows(pattern.len())
+                                                .position(|window| window == *pattern)
+                                                .map(|n| n + start);
+
+                assert_eq!(finder.find(&haystack[start..]).map(|n| n + start), expected);
+                assert_eq!(bs_find_pattern!(c, start, pattern), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn find_pattern_adversarial() {
+    // quadratic searching compares every window in full, which takes far too long here
+    let mut boundary = vec![b'a'; 4095];
+    let mut run      = vec![b'a'; 1 << 22];
+
+    boundary.push(b'b');
+    run.extend_from_slice(&boundary);
+
+    let c = ByteStream::new(&run);
+
+    assert_eq!(bs_find_pattern!(c, boundary), Some(1 << 22));
+    assert_eq!(bs_find_pattern!(c, 1, boundary), Some(1 << 22));
+}
+
+#[test]
+fn find_multi() {
+    let finder = MultiFinder::new([&b"\r\n"[..], b";", b",", b"\""], MatchKind::LeftmostFirst);
+    let c      = ByteStream::new(b"text/html; q=0.8, \"x\"\r\n");
+
+    assert_eq!(bs_find_multi!(c, finder), Some(Match{ end: 10, pattern: 1, start: 9 }));
+    assert_eq!(bs_find_multi!(c, 10, finder), Some(Match{ end: 17, pattern: 2, start: 16 }));
+    assert_eq!(bs_find_multi!(c, 21, finder), Some(Match{ end: 23, pattern: 0, start: 21 }));
+    assert_eq!(bs_find_multi!(c, 23, finder), None);
+}
+
+#[test]
+fn find_multi_semantics() {
+    let first   = MultiFinder::new(vec!["Sam", "Samwise"], MatchKind::LeftmostFirst);
+    let longest = MultiFinder::new(vec!["Sam", "Samwise"], MatchKind::LeftmostLongest);
+
+    assert_eq!(first.kind(), MatchKind::LeftmostFirst);
+    assert_eq!(first.find(b"Samwise"), Some(Match{ end: 3, pattern: 0, start: 0 }));
+    assert_eq!(longest.find(b"Samwise"), Some(Match{ end: 7, pattern: 1, start: 0 }));
+
+    let first = MultiFinder::new(vec!["Samwise", "Sam"], MatchKind::LeftmostFirst);
+
+    assert_eq!(first.find(b"Samwise"), Some(Match{ end: 7, pattern: 0, start: 0 }));
+    assert_eq!(first.find(b"Samwis"), Some(Match{ end: 3, pattern: 1, start: 0 }));
+}
+
+#[test]
+fn find_multi_naive() {
+    let haystacks: &[&[u8]] = &[
+        b"abcdabcabcdeabxabcdefg", b"she sells sea shells by the sea shore", b"aaaaaaaaab", b""
+    ];
+    let sets: &[&[&[u8]]] = &[
+        &[b"abcd", b"bc", b"c", b"abcde"],
+        &[b"he", b"she", b"his", b"hers", b"sea shore", b"shell"],
+        &[b"aab", b"ab", b"b", b"aaaaaaaaaa", b""],
+        &[b"x", b"zz"]
+    ];
+
+    for haystack in haystacks.iter() {
+        for patterns in sets.iter() {
+            for kind in [MatchKind::LeftmostFirst, MatchKind::LeftmostLongest].iter() {
+                let finder       = MultiFinder::new(patterns.iter(), *kind);
+                let mut expected = None;
+
+                'outer:
+                for start in 0..haystack.len() {
+                    let mut best: Option<Match> = None;
+
+                    for (id, pattern) in patterns.iter().enumerate() {
+                        if !pattern.is_empty() && haystack[start..].starts_with(pattern) {
+                            let candidate = Match{
+                                end:     start + pattern.len(),
+                                pattern: id,
+                                start
+                            };
+
+                            best = match best {
+                                Some(m) if *kind == MatchKind::LeftmostFirst
+                                        || m.end >= candidate.end => Some(m),
+                                _ => Some(candidate)
+                            };
+                        }
+                    }
+
+                    if best.is_some() {
+                        expected = best;
+
+                        break 'outer;
+                    }
+                }
+
+                assert_eq!(finder.find(haystack), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn find_pattern_ignore_case() {
+    let c = ByteStream::new(b"Content-Type: text/html; CHARSET=utf-8");
+
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"charset"), Some(25));
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"CONTENT-type"), Some(0));
+    assert_eq!(bs_find_pattern_ignore_case!(c, 1, b"content-type"), None);
+    assert_eq!(bs_find_pattern_ignore_case!(c, 100, b"charset"), None);
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"UTF-8"), Some(33));
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"-8"), Some(36));
+    assert_eq!(bs_find_pattern_ignore_case!(c, b""), None);
+}
+
+#[test]
+fn find_pattern_ignore_case_high_bytes() {
+    let c = ByteStream::new(b"\xC0\xE0 a\xC0");
+
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"A\xC0"), Some(3));
+    assert_eq!(bs_find_pattern_ignore_case!(c, b"\xE0\xE0"), None);
+}
+
 #[test]
 fn find_pattern1() {
     let c = ByteStream::new(b"stream data pattern search");
@@ -944,15 +1899,187 @@ fn replay() {
 }
 
 #[test]
-fn rewind() {
-    let mut c = ByteStream::new(b"stream");
+fn resume_bounded() {
+    let mut s = NumberState::new(0u64);
+    let mut c = ByteStream::new(b"fff");
 
-    bs_mark!(c);
-    bs_next!(c);
-    bs_next!(c);
-    bs_next!(c);
-    bs_next!(c);
-    bs_next!(c);
+    bs_resume_bounded!(c, s, 16, DigitPolicy::max(4), panic!(), panic!(), break);
+
+    let mut c = ByteStream::new(b"f\r\n");
+
+    bs_resume_bounded!(c, s, 16, DigitPolicy::max(4), panic!(), panic!(), panic!());
+
+    assert_eq!(s.value, 0xFFFF);
+    assert_eq!(s.digits, 4);
+    assert_eq!(c.byte, b'\r');
+
+    let mut s = NumberState::new(0u64);
+    let mut c = ByteStream::new(b"fff");
+
+    bs_resume_bounded!(c, s, 16, DigitPolicy::max(4), panic!(), panic!(), break);
+
+    let mut c = ByteStream::new(b"ff\r\n");
+
+    bs_resume_bounded!(c, s, 16, DigitPolicy::max(4), panic!(), break, panic!());
+
+    assert_eq!(s.value, 0xFFFF);
+    assert_eq!(s.digits, 4);
+    assert_eq!(bs_index!(c), 2);
+}
+
+#[test]
+fn resume_bounded_leading_zeros() {
+    let policy = DigitPolicy{
+        leading_zeros: false,
+        ..DigitPolicy::UNBOUNDED
+    };
+
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"0");
+
+    bs_resume_bounded!(c, s, 16, policy, panic!(), panic!(), break);
+
+    assert!(s.leading_zero);
+
+    let mut c = ByteStream::new(b"1\r\n");
+
+    bs_resume_bounded!(c, s, 16, policy, panic!(), break, panic!());
+
+    assert_eq!(s.value, 0);
+    assert_eq!(c.byte, b'1');
+
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"1");
+
+    bs_resume_bounded!(c, s, 16, policy, panic!(), panic!(), break);
+
+    let mut c = ByteStream::new(b"0\r\n");
+
+    bs_resume_bounded!(c, s, 16, policy, panic!(), panic!(), panic!());
+
+    assert!(!s.leading_zero);
+    assert_eq!(s.value, 0x10);
+}
+
+#[test]
+fn resume_prefixed() {
+    let mut s = NumberState::new(0u32);
+    let mut c = ByteStream::new(b"0");
+
+    bs_resume_prefixed!(c, s, panic!(), break);
+
+    let mut c = ByteStream::new(b"x1F;");
+
+    bs_resume_prefixed!(c, s, panic!(), panic!());
+
+    assert_eq!(s.value, 0x1F);
+    assert_eq!(s.radix, 16);
+    assert_eq!(c.byte, b';');
+
+    let mut s = NumberState::new(0u32);
+    let mut c = ByteStream::new(b"0");
+
+    bs_resume_prefixed!(c, s, panic!(), break);
+
+    let mut c = ByteStream::new(b"17;");
+
+    bs_resume_prefixed!(c, s, panic!(), panic!());
+
+    assert_eq!(s.value, 17);
+    assert_eq!(s.radix, 0);
+
+    let mut s = NumberState::new(0u32);
+    let mut c = ByteStream::new(b"0b");
+
+    bs_resume_prefixed!(c, s, panic!(), break);
+
+    let mut c = ByteStream::new(b"101b");
+
+    bs_resume_prefixed!(c, s, panic!(), panic!());
+
+    assert_eq!(s.value, 5);
+    assert_eq!(c.byte, b'b');
+}
+
+#[test]
+fn resume_radix() {
+    let mut s = NumberState::new(0u32);
+    let mut c = ByteStream::new(b"12");
+
+    bs_resume_radix!(c, s, 10, panic!(), break);
+
+    assert!(s.is_started());
+    assert_eq!(s.value, 12);
+
+    let mut c = ByteStream::new(b"345;");
+
+    bs_resume_radix!(c, s, 10, panic!(), panic!());
+
+    assert_eq!(s.value, 12345);
+    assert_eq!(s.digits, 5);
+    assert_eq!(c.byte, b';');
+}
+
+#[test]
+fn resume_radix_hex() {
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"f");
+
+    bs_resume_radix!(c, s, 16, panic!(), break);
+
+    let mut c = ByteStream::new(b"f0");
+
+    bs_resume_radix!(c, s, 16, break, panic!());
+
+    assert_eq!(s.value, 0xFF);
+    assert_eq!(s.digits, 2);
+    assert_eq!(c.byte, b'0');
+}
+
+#[test]
+fn resume_radix_empty() {
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"");
+
+    bs_resume_radix!(c, s, 10, panic!(), break);
+
+    assert!(!s.is_started());
+}
+
+#[test]
+fn resume_signed() {
+    let mut s = NumberState::new(0i64);
+    let mut c = ByteStream::new(b"-");
+
+    bs_resume_signed!(c, s, panic!(), break);
+
+    assert!(s.is_started());
+    assert!(s.negative);
+    assert_eq!(s.digits, 0);
+
+    let mut c = ByteStream::new(b"922337203");
+
+    bs_resume_signed!(c, s, panic!(), break);
+
+    let mut c = ByteStream::new(b"6854775808-");
+
+    bs_resume_signed!(c, s, panic!(), panic!());
+
+    assert_eq!(s.value, i64::MIN);
+    assert_eq!(s.digits, 19);
+    assert_eq!(c.byte, b'-');
+}
+
+#[test]
+fn rewind() {
+    let mut c = ByteStream::new(b"stream");
+
+    bs_mark!(c);
+    bs_next!(c);
+    bs_next!(c);
+    bs_next!(c);
+    bs_next!(c);
+    bs_next!(c);
     bs_next!(c);
 
     assert_eq!(bs_slice!(c), b"stream");
@@ -1005,6 +2132,148 @@ fn rewind_to() {
     assert_eq!(bs_slice!(c), b"");
 }
 
+#[test]
+fn rfind() {
+    let mut c = ByteStream::new(b"/usr/local/bin/tool");
+
+    assert_eq!(bs_rfind!(c, b'/'), Some(14));
+    assert_eq!(bs_rfind!(c, 15, b'/'), None);
+    assert_eq!(bs_rfind!(c, 100, b'/'), None);
+    assert_eq!(bs_rfind!(c, b'!'), None);
+
+    bs_jump!(c, 15);
+
+    assert_eq!(bs_rfind!(c, b'/'), None);
+    assert_eq!(bs_rfind!(c, b't'), Some(15));
+}
+
+#[test]
+fn rfind_long() {
+    for length in 0..100 {
+        for position in 0..length + 1 {
+            let mut a = vec![b' '; length];
+
+            if position < length {
+                a[position] = b'x';
+
+                if position > 3 {
+                    a[position - 3] = b'x';
+                }
+            }
+
+            let c        = ByteStream::new(&a);
+            let expected = if position < length { Some(position) } else { None };
+
+            assert_eq!(bs_rfind!(c, b'x'), expected);
+            assert_eq!(::search::rfind_swar(&a, [b'x']), expected);
+
+            for start in 0..length + 1 {
+                let expected = a[start..].iter().rposition(|byte| *byte == b'x').map(|n| n + start);
+
+                assert_eq!(bs_rfind!(c, start, b'x'), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn rfind_pattern() {
+    let c = ByteStream::new(b"body\r\n--boundary\r\nbody\r\n--boundary--\r\n");
+
+    assert_eq!(bs_rfind_pattern!(c, b"\r\n--boundary"), Some(22));
+    assert_eq!(bs_rfind_pattern!(c, 4, b"\r\n--boundary"), Some(22));
+    assert_eq!(bs_rfind_pattern!(c, 23, b"\r\n--boundary"), None);
+    assert_eq!(bs_rfind_pattern!(c, b"missing"), None);
+    assert_eq!(bs_rfind_pattern!(c, b""), None);
+}
+
+#[test]
+fn rfind_before() {
+    let mut c = ByteStream::new(b"/static/css/site.css HTTP/1.1");
+
+    assert_eq!(bs_rfind_before!(c, b'/'), None);
+
+    bs_collect_until!(c, c.byte == b' ', panic!());
+
+    assert_eq!(bs_rfind_before!(c, b'/'), Some(11));
+    assert_eq!(bs_rfind_before!(c, b'!'), None);
+    assert_eq!(bs_rfind_pattern_before!(c, b"css"), Some(17));
+    assert_eq!(bs_rfind_pattern_before!(c, b"css "), Some(17));
+    assert_eq!(bs_rfind_pattern_before!(c, b"HTTP"), None);
+    assert_eq!(bs_rfind_pattern_before!(c, b""), None);
+
+    bs_mark!(c);
+    bs_jump!(c, 8);
+
+    assert_eq!(bs_rfind_before!(c, b'/'), Some(25));
+    assert_eq!(bs_rfind_pattern_before!(c, b"/static"), Some(0));
+}
+
+#[test]
+fn rfind_with() {
+    let finder = FinderRev::new(b"\r\n--boundary").into_owned();
+    let c      = ByteStream::new(b"preamble\r\n--boundary\r\npart\r\n--boundary--");
+
+    assert_eq!(finder.pattern(), b"\r\n--boundary");
+    assert_eq!(bs_rfind_with!(c, finder), Some(26));
+    assert_eq!(bs_rfind_with!(c, 9, finder), Some(26));
+    assert_eq!(bs_rfind_with!(c, 27, finder), None);
+    assert_eq!(FinderRev::new(b"").rfind(b"haystack"), None);
+}
+
+#[test]
+fn rfind_pattern_naive() {
+    let mut boundary = vec![b'b'];
+    let mut run      = Vec::new();
+
+    boundary.extend_from_slice(&[b'a'; 69]);
+    run.extend_from_slice(&boundary);
+    run.extend_from_slice(&[b'a'; 200]);
+
+    let haystacks: &[&[u8]] = &[
+        b"baaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        b"abababababababababababababababababababababababababababababababaabab",
+        b"--X----------------------------------------------------------------",
+        b"the quick brown fox jumps over the lazy dog, the quick brown fox!",
+        &run
+    ];
+    let patterns: &[&[u8]] = &[
+        b"a", b"b", b"ab", b"baa", b"baaa", b"abab", b"baab", b"X", b"-X-", b"X---",
+        b"fox!", b"the", b"dog,", b"lazy dog", b"quick brown fox jumps", b"cat", &boundary
+    ];
+
+    for haystack in haystacks.iter() {
+        for pattern in patterns.iter() {
+            let finder = FinderRev::new(pattern);
+            let mut c  = ByteStream::new(haystack);
+
+            for end in 0..haystack.len() + 1 {
+                let expected = haystack[..end].windows(pattern.len())
+                                              .rposition(|window| window == *pattern);
+
+                c.stream_index = end;
+
+                assert_eq!(finder.rfind(&haystack[..end]), expected);
+                assert_eq!(bs_rfind_pattern_before!(c, pattern), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn rfind_pattern_adversarial() {
+    let mut boundary = vec![b'b'];
+    let mut run      = Vec::new();
+
+    boundary.extend_from_slice(&[b'a'; 4095]);
+    run.extend_from_slice(&boundary);
+    run.extend_from_slice(&vec![b'a'; 1 << 22]);
+
+    let c = ByteStream::new(&run);
+
+    assert_eq!(bs_rfind_pattern!(c, boundary), Some(0));
+}
+
 #[test]
 fn slice_length() {
     let mut c = ByteStream::new(b"stream");
@@ -1060,6 +2329,32 @@ fn starts_with() {
     assert!(bs_starts_with!(c, b"stream data"));
 }
 
+#[test]
+fn starts_with_ignore_case() {
+    let c = ByteStream::new(b"Transfer-Encoding: chunked");
+
+    assert!(bs_starts_with_ignore_case!(c, b"transfer-encoding"));
+    assert!(bs_starts_with_ignore_case!(c, b"TRANSFER-ENCODING:"));
+    assert!(bs_starts_with_ignore_case!(c, b"tRANSFER"));
+    assert!(!bs_starts_with_ignore_case!(c, b"transfer_encoding"));
+    assert!(bs_starts_with_ignore_case!(c, b""));
+}
+
+#[test]
+fn starts_with_ignore_case_test() {
+    for n in 0..=255u8 {
+        for m in 0..=255u8 {
+            let a        = [b'x', b'x', b'x', b'x', b'x', b'x', b'x', n, b'x'];
+            let b        = [b'X', b'X', b'X', b'X', b'X', b'X', b'X', m, b'x'];
+            let c        = ByteStream::new(&a);
+            let expected = n.eq_ignore_ascii_case(&m);
+
+            assert_eq!(bs_starts_with_ignore_case!(c, b), expected);
+            assert_eq!(bs_starts_with_ignore_case!(c, b[..8]), expected);
+        }
+    }
+}
+
 #[test]
 fn starts_with1() {
     let c = ByteStream::new(b"s");
@@ -1234,3 +2529,1190 @@ fn starts_with25() {
 
     assert!(bs_starts_with!(c, b"stream data stream data s"));
 }
+
+#[test]
+fn starts_with_aliases() {
+    let c = ByteStream::new(b"stream data stream data s");
+
+    assert!(bs_starts_with1!(c, b"stream data"));
+    assert!(bs_starts_with5!(c, b"streax"));
+    assert!(!bs_starts_with6!(c, b"streax"));
+    assert!(bs_starts_with25!(c, b"stream data stream data s"));
+    assert!(!bs_starts_with25!(c, b"stream data stream data x"));
+}
+
+#[test]
+fn starts_with_array() {
+    let mut c = ByteStream::new(b"GET /index.html HTTP/1.1\r\nHost: example.com\r\n");
+
+    assert!(bs_starts_with_array!(c, b""));
+    assert!(bs_starts_with_array!(c, b"GET "));
+    assert!(!bs_starts_with_array!(c, b"GET!"));
+    assert!(bs_starts_with_array!(c, b"GET /index.html HTTP/1.1\r\nHost: "));
+    assert!(!bs_starts_with_array!(c, b"GET /index.html HTTP/1.1\r\nHost! "));
+
+    bs_jump!(c, 16);
+
+    assert!(bs_starts_with_array!(c, b"HTTP/1.1\r\n"));
+    assert!(bs_starts_with_n!(c, 4, b"HTTP/2"));
+}
+
+#[test]
+fn starts_with_array_test() {
+    let a = *b"0123456789abcdefghijklmnopqrstuv";
+
+    for n in 0..a.len() {
+        let mut b = a;
+
+        b[n] ^= 0x20;
+
+        let c = ByteStream::new(&a);
+
+        assert!(bs_starts_with_array!(c, &a));
+        assert!(!bs_starts_with_array!(c, &b));
+        assert_eq!(bs_starts_with_n!(c, 31, b), n == 31);
+        assert_eq!(bs_starts_with_n!(c, 7, b), n >= 7);
+        assert_eq!(bs_starts_with_n!(c, 1, b), n >= 1);
+    }
+}
+
+#[test]
+fn try_next() {
+    let mut c = ByteStream::new(b"ab");
+
+    assert_eq!(bs_try_next!(c), Some(b'a'));
+    assert_eq!(bs_try_next!(c), Some(b'b'));
+    assert_eq!(bs_try_next!(c), None);
+    assert_eq!(c.byte, b'b');
+    assert_eq!(c.stream_index, 2);
+}
+
+#[test]
+fn try_starts_with() {
+    let mut c = ByteStream::new(b"stream data");
+
+    assert!(bs_try_starts_with!(c, b"stream data"));
+    assert!(!bs_try_starts_with!(c, &b"stream data!"[..]));
+    assert!(bs_try_starts_with_array!(c, b"stream data"));
+    assert!(!bs_try_starts_with_array!(c, b"stream data!"));
+    assert!(bs_try_starts_with_n!(c, 1, b"s"));
+    assert!(bs_try_starts_with_n!(c, 6, b"stream!"));
+    assert!(!bs_try_starts_with_n!(c, 6, b"strea"));
+    assert!(!bs_try_starts_with_n!(c, 12, b"stream data "));
+
+    bs_jump!(c, 7);
+
+    assert!(bs_try_starts_with_n!(c, 4, b"data"));
+    assert!(!bs_try_starts_with_n!(c, 5, b"data "));
+    assert!(bs_try_starts_with_n!(c, 0, b""));
+
+    bs_jump!(c, 4);
+
+    assert!(!bs_try_starts_with_n!(c, 1, b"d"));
+    assert!(bs_try_starts_with_array!(c, b""));
+}
+
+#[test]
+fn try_starts_with_past_end() {
+    let c = Context{
+        byte:         0,
+        mark_index:   0,
+        stream:       b"stream",
+        stream_index: 8
+    };
+
+    assert!(!c.try_starts_with(b"s"));
+    assert!(!c.try_starts_with(b""));
+}
+
+#[cfg(all(feature = "debug-checks", debug_assertions))]
+#[test]
+#[should_panic]
+fn debug_checks_next() {
+    let mut c = ByteStream::new(b"");
+
+    unsafe { c.next_unchecked(); }
+}
+
+#[cfg(all(feature = "debug-checks", debug_assertions))]
+#[test]
+#[should_panic]
+fn debug_checks_starts_with() {
+    let c = ByteStream::new(b"stream");
+
+    bs_starts_with7!(c, b"stream!");
+}
+
+#[test]
+fn match_arms() {
+    let mut c = ByteStream::new(b"POST /index.html");
+
+    let method = bs_match!(c, {
+        b"GET"  => 1,
+        b"POST" => 2,
+        b"PUT"  => 3,
+        _       => 0
+    });
+
+    assert_eq!(method, 2);
+    assert_eq!(c.byte, b'T');
+    assert_eq!(c.stream_index, 4);
+
+    let method = bs_match!(c, {
+        b"GET"  => 1,
+        b"POST" => 2,
+        _       => 0,
+    });
+
+    assert_eq!(method, 0);
+    assert_eq!(c.stream_index, 4);
+}
+
+#[test]
+fn match_arms_loop() {
+    let mut c       = ByteStream::new(b"GET HEAD PUT DELETE POST");
+    let mut methods = Vec::new();
+
+    loop {
+        bs_match!(c, {
+            _        => break,
+            b"GET"   => methods.push(1),
+            b"HEAD"  => {
+                bs_next!(c);
+                continue;
+            },
+            b"POST"  => methods.push(3),
+            b"PUT"   => methods.push(4),
+        });
+
+        if bs_is_eos!(c) {
+            break;
+        }
+
+        bs_next!(c);
+    }
+
+    assert_eq!(methods, vec![1, 4]);
+    assert_eq!(bs_remaining!(c), b"DELETE POST");
+
+    bs_jump!(c, 7);
+
+    bs_match!(c, {
+        b"POST" => methods.push(3)
+    });
+
+    bs_match!(c, {
+        b"POST" => methods.push(3)
+    });
+
+    assert_eq!(methods, vec![1, 4, 3]);
+}
+
+#[test]
+fn match_keywords() {
+    let mut c = ByteStream::new(b"INSERT INTO INNER IN");
+
+    assert_eq!(bs_match!(c, [b"INSERT", b"INTO", b"INNER", b"IN"]), Some(0));
+    assert_eq!(c.stream_index, 6);
+    assert_eq!(bs_match!(c, [b"INSERT", b"INTO", b"INNER", b"IN"]), None);
+
+    bs_next!(c);
+
+    assert_eq!(bs_match!(c, [b"INSERT", b"INTO", b"INNER", b"IN"]), Some(1));
+    assert_eq!(c.byte, b'O');
+
+    bs_next!(c);
+
+    assert_eq!(bs_match!(c, [b"INSERT", b"INTO", b"INNER", b"IN"]), Some(2));
+
+    bs_next!(c);
+
+    // "INSERT" and "INTO" share the leading byte but are longer than the remaining bytes
+    assert_eq!(bs_match!(c, [b"INSERT", b"INTO", b"INNER", b"IN",]), Some(3));
+    assert!(c.is_eos());
+    assert_eq!(bs_match!(c, [b"IN"]), None);
+}
+
+#[test]
+fn keywords() {
+    const KEYWORDS: Keywords<3> = Keywords::new([b"GET", b"GETS", b"HEAD"]);
+
+    assert_eq!(KEYWORDS.find(b"GETS"), Some(1));
+    assert_eq!(KEYWORDS.find(b"GET "), Some(0));
+    assert_eq!(KEYWORDS.find(b"GE"), None);
+    assert_eq!(KEYWORDS.find(b"HEAD"), Some(2));
+    assert_eq!(KEYWORDS.find(b"HEA"), None);
+    assert_eq!(KEYWORDS.find(b""), None);
+    assert_eq!(KEYWORDS.keywords().len(), 3);
+}
+
+#[test]
+fn keywords_longest() {
+    const KEYWORDS: Keywords<5> = Keywords::new([b"GET", b"GETALL", b"G", b"GETA", b"GET"]);
+
+    assert_eq!(KEYWORDS.find(b"GETALL"), Some(1));
+    assert_eq!(KEYWORDS.find(b"GETAL"), Some(3));
+    assert_eq!(KEYWORDS.find(b"GET"), Some(0));
+    assert_eq!(KEYWORDS.find(b"GE"), Some(2));
+
+    let mut c = ByteStream::new(b"GETALL");
+
+    let method = bs_match!(c, {
+        b"GET"    => 1,
+        b"GETALL" => 2,
+        _         => 0
+    });
+
+    assert_eq!(method, 2);
+    assert!(c.is_eos());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_stream() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{ Context, Poll, Wake, Waker };
+    use stream::AsyncStream;
+    use tokio::io::{ self, AsyncWrite };
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {
+        }
+    }
+
+    fn poll<F: Future + Unpin>(mut future: F) -> Poll<F::Output> {
+        let waker = Waker::from(Arc::new(Noop));
+
+        Pin::new(&mut future).poll(&mut Context::from_waker(&waker))
+    }
+
+    fn write<W: AsyncWrite + Unpin>(writer: &mut W, data: &[u8]) {
+        let waker       = Waker::from(Arc::new(Noop));
+        let mut context = Context::from_waker(&waker);
+
+        match Pin::new(writer).poll_write(&mut context, data) {
+            Poll::Ready(Ok(length)) => assert_eq!(length, data.len()),
+            _ => panic!()
+        }
+    }
+
+    let (reader, mut writer) = io::duplex(64);
+    let mut c                = AsyncStream::with_read_size(reader, 16);
+
+    assert!(poll(c.fill()).is_pending());
+
+    write(&mut writer, b"Content-Le");
+
+    assert!(matches!(poll(c.fill()), Poll::Ready(Ok(true))));
+    assert_eq!(bs_collect_set!(c.stream, ByteSet::ALPHA.with(b'-')), Collect::Eos(10));
+
+    write(&mut writer, b"ngth: 42\r\n");
+
+    assert!(matches!(poll(c.fill()), Poll::Ready(Ok(true))));
+    assert_eq!(bs_collect_set!(c.stream, ByteSet::ALPHA.with(b'-')), Collect::Done(4));
+    assert_eq!(bs_slice_ignore!(c.stream), b"Content-Length");
+
+    bs_jump!(c.stream, 1);
+    bs_mark!(c.stream);
+
+    assert_eq!(c.stream.collect_digits::<u8>(), Collect::Done(42));
+    assert_eq!(bs_slice_ignore!(c.stream), b"42");
+
+    drop(writer);
+
+    assert!(matches!(poll(c.fill()), Poll::Ready(Ok(false))));
+    assert!(c.is_finished());
+    assert_eq!(c.stream.offset(), 16);
+    assert_eq!(bs_remaining!(c.stream), b"\n");
+}
+
+/// Percent-decode `bytes` in place, and return the decoded length.
+fn percent_decode(bytes: &mut [u8]) -> Option<usize> {
+    let mut c = ByteStreamMut::new(bytes);
+
+    loop {
+        bs_mark!(c);
+        bs_collect_until!(c, c.byte == b'%' || c.byte == b'+', {
+            bs_compact!(c);
+
+            return Some(bs_written!(c).len());
+        });
+        bs_compact_ignore!(c);
+
+        if c.byte == b'+' {
+            bs_write_byte!(c, b' ');
+        } else if bs_has_bytes!(c, 2) {
+            let value = c.peek(2).iter().try_fold(0, |value, byte| {
+                (*byte as char).to_digit(16).map(|digit| value * 16 + digit as u8)
+            })?;
+
+            bs_jump!(c, 2);
+            bs_write_byte!(c, value);
+        } else {
+            return None;
+        }
+    }
+}
+
+#[test]
+fn byte_stream_mut() {
+    let mut a = *b"a%20b%2Fc+d%41";
+
+    assert_eq!(percent_decode(&mut a), Some(8));
+    assert_eq!(&a[..8], b"a b/c dA");
+
+    let mut a = *b"plain";
+
+    assert_eq!(percent_decode(&mut a), Some(5));
+    assert_eq!(&a, b"plain");
+
+    let mut a = *b"%4";
+
+    assert_eq!(percent_decode(&mut a), None);
+
+    let mut a = *b"%zz";
+
+    assert_eq!(percent_decode(&mut a), None);
+}
+
+#[test]
+fn byte_stream_mut_compact() {
+    let mut a = *b"Host:  example.com ";
+    let mut c = ByteStreamMut::new(&mut a);
+
+    bs_collect_until!(c, c.byte == b':', panic!());
+    bs_compact!(c);
+    bs_skip_set!(c, ByteSet::bytes(b" "));
+    bs_mark!(c);
+    bs_collect_until!(c, c.byte == b' ', panic!());
+    bs_compact_ignore!(c);
+
+    assert_eq!(c.write_index, 16);
+    assert_eq!(c.mark_index, c.stream_index);
+    assert_eq!(format!("{:?}", c),
+               "ByteStreamMut(byte[32]=' ', mark_index=19, stream_index=19, write_index=16)");
+    assert_eq!(c.written(), b"Host:example.com");
+
+    c.write_byte(b'!');
+
+    assert_eq!(c.into_written(), b"Host:example.com!");
+}
+
+#[test]
+#[should_panic]
+fn byte_stream_mut_write_past() {
+    let mut a = *b"ab";
+    let mut c = ByteStreamMut::new(&mut a);
+
+    bs_next!(c);
+    bs_write_byte!(c, b'x');
+    bs_write_byte!(c, b'y');
+}
+
+#[test]
+#[should_panic]
+fn byte_stream_mut_compact_past() {
+    let mut a = *b"abcdef";
+    let mut c = ByteStreamMut::new(&mut a);
+
+    bs_jump!(c, 2);
+    bs_write_byte!(c, b'X');
+    bs_write_byte!(c, b'Y');
+    bs_jump!(c, 2);
+    bs_compact!(c);
+}
+
+#[test]
+fn byte_set_classifiers() {
+    for byte in 0..=255u8 {
+        assert_eq!(ByteSet::ALPHA.contains(byte), is_alpha!(byte));
+        assert_eq!(ByteSet::CONTROL.contains(byte), is_control!(byte));
+        assert_eq!(ByteSet::DIGIT.contains(byte), is_digit!(byte));
+        assert_eq!(ByteSet::HEX.contains(byte), is_hex!(byte));
+        assert_eq!(ByteSet::NOT_VISIBLE_7BIT.contains(byte), is_not_visible_7bit!(byte));
+        assert_eq!(ByteSet::NOT_VISIBLE_8BIT.contains(byte), is_not_visible_8bit!(byte));
+        assert_eq!(ByteSet::VISIBLE_7BIT.contains(byte), is_visible_7bit!(byte));
+        assert_eq!(ByteSet::VISIBLE_8BIT.contains(byte), is_visible_8bit!(byte));
+    }
+}
+
+#[test]
+fn byte_set_construction() {
+    const SET: ByteSet = ByteSet::bytes(b"az").with(0xFF).with_range(b'0', b'2');
+
+    assert_eq!(SET.iter().collect::<Vec<u8>>(), vec![b'0', b'1', b'2', b'a', b'z', 0xFF]);
+    assert_eq!(SET.len(), 6);
+    assert!(!SET.is_empty());
+    assert!(ByteSet::EMPTY.is_empty());
+    assert_eq!(ByteSet::ALL.len(), 256);
+    assert_eq!(ByteSet::range(0x00, 0xFF), ByteSet::ALL);
+    assert_eq!(ByteSet::range(b'b', b'a'), ByteSet::EMPTY);
+    assert_eq!(ByteSet::default(), ByteSet::EMPTY);
+    assert_eq!(format!("{:?}", ByteSet::bytes(b"ab")), "{97, 98}");
+}
+
+#[test]
+fn byte_set_operations() {
+    let set = ByteSet::HEX;
+
+    assert_eq!(set | ByteSet::ALPHA, ByteSet::ALPHANUMERIC);
+    assert_eq!(set & ByteSet::ALPHA, ByteSet::range(b'A', b'F').with_range(b'a', b'f'));
+    assert_eq!(set - ByteSet::ALPHA, ByteSet::DIGIT);
+    assert_eq!(!set, set.complement());
+    assert_eq!((!set).len(), 256 - 22);
+    assert_eq!(set.union(!set), ByteSet::ALL);
+    assert_eq!(set.intersection(!set), ByteSet::EMPTY);
+    assert_eq!(ByteSet::VISIBLE_7BIT.difference(ByteSet::ALPHANUMERIC).len(), 32);
+}
+
+#[test]
+fn chunked_stream() {
+    let mut c = ChunkedStream::new();
+
+    c.push(b"GET /ind");
+
+    bs_collect_until!(c, c.byte == b' ', panic!());
+    bs_mark!(c);
+    bs_collect_until!(c, c.byte == b' ', break);
+
+    assert!(bs_is_eos!(c));
+    assert_eq!(bs_slice!(c), b"/ind");
+
+    c.push(b"ex.html HTTP/1.1");
+
+    assert_eq!(c.offset(), 4);
+    assert_eq!(c.mark_index, 0);
+    assert_eq!(c.stream_index, 4);
+
+    bs_collect_until!(c, c.byte == b' ', panic!());
+
+    assert_eq!(bs_slice_ignore!(c), b"/index.html");
+    assert_eq!(bs_find_pattern!(c, b"1.1"), Some(17));
+
+    bs_mark!(c);
+    c.push(b"\r\n");
+
+    assert_eq!(c.offset(), 16);
+    assert_eq!(bs_remaining!(c), b"HTTP/1.1\r\n");
+    assert_eq!(format!("{:?}", c),
+               "ChunkedStream(byte[32]=' ', mark_index=0, offset=16, stream_index=0)");
+}
+
+#[test]
+fn chunked_stream_resume() {
+    let mut c     = ChunkedStream::with_capacity(16);
+    let mut state = NumberState::new(0u32);
+
+    c.push(b"Content-Length: 12");
+
+    bs_jump!(c, 16);
+    bs_mark!(c);
+
+    assert_eq!(c.resume_radix(10, &mut state), Collect::Eos(12));
+
+    c.push(b"34\r\n");
+
+    assert_eq!(c.resume_radix(10, &mut state), Collect::Done(1234));
+    assert_eq!(bs_slice_ignore!(c), b"1234");
+    assert_eq!(c.offset(), 16);
+}
+
+#[test]
+fn read_stream() {
+    let data   = b"Content-Length: 1234567890\r\nX: 1.5e3 ";
+    let reader = StepReader{ data, error: false, step: 3 };
+    let mut c  = ReadStream::with_read_size(reader, 4);
+
+    assert!(bs_is_eos!(c));
+    assert_eq!(bs_collect_set!(c, ByteSet::ALPHA.with(b'-')), Collect::Done(14));
+    assert_eq!(c.byte, b':');
+
+    bs_collect_until!(c, c.byte != b' ', panic!());
+    bs_replay!(c);
+    bs_mark!(c);
+
+    assert_eq!(c.collect_digits::<u64>(), Collect::Done(1234567890));
+    assert_eq!(bs_slice_ignore!(c), b"1234567890");
+    assert_eq!(c.offset() + c.mark_index, 16);
+    assert_eq!(bs_find_fill!(c, b' '), Some(c.index() + 3));
+    assert!(bs_ensure!(c, 4));
+
+    bs_jump!(c, 4);
+    bs_mark!(c);
+
+    assert_eq!(c.collect_float::<f64>(&FloatFormat::STRICT), FloatCollect::Done(1500.0));
+    assert_eq!(c.offset() + c.mark_index, 31);
+    assert!(!bs_ensure!(c, 1));
+    assert!(c.is_finished());
+    assert!(c.take_error().is_none());
+}
+
+#[test]
+fn read_stream_bounded() {
+    let mut data = Vec::new();
+
+    for n in 0..1000 {
+        data.extend_from_slice(format!("{} ", n).as_bytes());
+    }
+
+    let reader = StepReader{ data: &data, error: false, step: 7 };
+    let mut c  = ReadStream::with_read_size(reader, 16);
+    let mut n  = 0;
+
+    loop {
+        bs_mark!(c);
+        c.release();
+
+        match c.collect_digits::<u32>() {
+            Collect::Done(value) => {
+                assert_eq!(value, n);
+                assert!(c.stream().len() <= 64);
+
+                n += 1;
+            },
+            Collect::Eos(_) => {
+                break;
+            },
+            _ => panic!()
+        }
+    }
+
+    assert_eq!(n, 1000);
+    assert_eq!(c.offset() + c.index(), data.len());
+}
+
+#[test]
+fn read_stream_bounded_long() {
+    let mut data = Vec::new();
+
+    for n in 0..200_000 {
+        data.extend_from_slice(format!("{}\r\n", n).as_bytes());
+    }
+
+    let mut c   = ReadStream::new(&data[..]);
+    let mut max = 0;
+    let mut n   = 0;
+
+    loop {
+        bs_mark!(c);
+        c.release();
+
+        match c.collect_digits::<u32>() {
+            Collect::Done(value) => {
+                assert_eq!(value, n);
+                assert_eq!(c.try_next(), Some(b'\n'));
+
+                max  = max.max(c.stream().len());
+                n   += 1;
+            },
+            Collect::Eos(_) => {
+                break;
+            },
+            _ => panic!()
+        }
+    }
+
+    assert_eq!(n, 200_000);
+    assert!(data.len() > 1 << 20);
+    assert!(max <= 2 * 8192);
+
+    // without releasing, every byte is retained
+    let mut c = ReadStream::new(&data[..]);
+
+    while let Collect::Done(_) = c.collect_digits::<u32>() {
+        assert_eq!(c.try_next(), Some(b'\n'));
+
+        bs_mark!(c);
+    }
+
+    assert_eq!(c.stream().len(), data.len());
+}
+
+#[test]
+fn read_stream_release() {
+    let reader = StepReader{ data: b"stream data", error: false, step: 11 };
+    let mut c  = ReadStream::new(reader);
+
+    assert!(bs_ensure!(c, 11));
+
+    bs_jump!(c, 8);
+    bs_mark!(c);
+
+    assert_eq!(c.index(), 8);
+    assert_eq!(c.mark_index, 8);
+    assert_eq!(c.offset(), 0);
+
+    c.release();
+
+    assert_eq!(c.index(), 0);
+    assert_eq!(c.mark_index, 0);
+    assert_eq!(c.offset(), 8);
+    assert_eq!(c.remaining(), b"ata");
+}
+
+#[test]
+fn read_stream_error() {
+    let reader = StepReader{ data: b"12", error: true, step: 1 };
+    let mut c  = ReadStream::new(reader);
+
+    assert_eq!(c.collect_digits::<u32>(), Collect::Eos(12));
+    assert!(c.is_finished());
+    assert_eq!(c.take_error().map(|error| error.kind()), Some(io::ErrorKind::Other));
+    assert!(!bs_fill!(c));
+}
+
+#[test]
+fn classes_rfc3986() {
+    for byte in 0..=255u8 {
+        let unreserved = is_alpha!(byte) || is_digit!(byte) || b"-._~".contains(&byte);
+        let gen_delim  = b":/?#[]@".contains(&byte);
+        let sub_delim  = b"!$&'()*+,;=".contains(&byte);
+
+        assert_eq!(classes::is_unreserved(byte), unreserved);
+        assert_eq!(classes::is_gen_delim(byte), gen_delim);
+        assert_eq!(classes::is_sub_delim(byte), sub_delim);
+        assert_eq!(classes::is_reserved(byte), gen_delim || sub_delim);
+        assert_eq!(classes::is_pchar(byte),
+                   unreserved || sub_delim || byte == b':' || byte == b'@' || byte == b'%');
+    }
+}
+
+#[test]
+fn classes_rfc4648() {
+    let base64     = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let base64_url = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    for byte in 0..=255u8 {
+        assert_eq!(classes::is_base64(byte), base64.contains(&byte));
+        assert_eq!(classes::is_base64_url(byte), base64_url.contains(&byte));
+    }
+
+    assert_eq!(classes::BASE64.len(), 64);
+    assert_eq!(classes::BASE64_URL.len(), 64);
+}
+
+#[test]
+fn classes_rfc5322() {
+    for byte in 0..=255u8 {
+        assert_eq!(classes::is_atext(byte),
+                   is_alpha!(byte) || is_digit!(byte) || b"!#$%&'*+-/=?^_`{|}~".contains(&byte));
+    }
+}
+
+#[test]
+fn classes_rfc7230() {
+    for byte in 0..=255u8 {
+        let obs_text = byte >= 0x80;
+        let vchar    = (0x21..=0x7E).contains(&byte);
+
+        assert_eq!(classes::is_tchar(byte),
+                   is_alpha!(byte) || is_digit!(byte) || b"!#$%&'*+-.^_`|~".contains(&byte));
+        assert_eq!(classes::is_obs_text(byte), obs_text);
+        assert_eq!(classes::is_vchar(byte), vchar);
+        assert_eq!(classes::is_field_vchar(byte), vchar || obs_text);
+        assert_eq!(classes::OBS_TEXT.contains(byte), obs_text);
+        assert_eq!(classes::VCHAR.contains(byte), vchar);
+    }
+}
+
+#[test]
+fn classes_collect() {
+    let mut c = ByteStream::new(b"Content-Type: text/html");
+
+    assert_eq!(bs_collect_set!(c, classes::TCHAR), Collect::Done(12));
+    assert_eq!(bs_skip_set!(c, ByteSet::bytes(b" ")), 1);
+
+    bs_mark!(c);
+    bs_collect_when!(c,
+        classes::is_field_vchar(c.byte),
+        {
+            break;
+        }
+    );
+
+    assert_eq!(c.byte, b'l');
+    assert_eq!(bs_slice!(c), b"text/html");
+}
+
+#[test]
+fn collect_set() {
+    let mut c = ByteStream::new(b"Content-Type: text/html");
+
+    assert_eq!(bs_collect_set!(c, ByteSet::ALPHA.with(b'-')), Collect::Done(12));
+    assert_eq!(c.byte, b':');
+    assert_eq!(c.stream_index, 13);
+    assert_eq!(bs_collect_set!(c, ByteSet::ALPHA), Collect::Done(0));
+    assert_eq!(c.byte, b' ');
+    assert_eq!(bs_collect_set!(c, ByteSet::ALPHA.with(b'/')), Collect::Eos(9));
+    assert_eq!(c.byte, b'l');
+    assert!(c.is_eos());
+    assert_eq!(bs_collect_set!(c, ByteSet::ALPHA), Collect::Eos(0));
+}
+
+#[test]
+fn skip_set() {
+    let mut c = ByteStream::new(b"  \t value");
+
+    assert_eq!(bs_skip_set!(c, ByteSet::bytes(b" \t")), 4);
+    assert_eq!(c.byte, b' ');
+    assert_eq!(c.stream_index, 4);
+    assert_eq!(bs_skip_set!(c, ByteSet::bytes(b" \t")), 0);
+    assert_eq!(c.stream_index, 4);
+    assert_eq!(bs_skip_set!(c, ByteSet::ALPHA), 5);
+    assert!(c.is_eos());
+}
+
+#[test]
+fn span_test() {
+    let sets = [ByteSet::EMPTY, ByteSet::DIGIT, ByteSet::HEX, ByteSet::bytes(b" \t"),
+                ByteSet::range(0x70, 0x7F), ByteSet::ALL];
+
+    for set in sets.iter() {
+        let members: Vec<u8> = set.iter().collect();
+
+        for length in 0..70 {
+            for stop in 0..length {
+                let mut a: Vec<u8> = members.iter().cloned().cycle().take(length).collect();
+
+                if a.len() < length {
+                    continue;
+                }
+
+                match (!*set).iter().next() {
+                    Some(byte) => a[stop] = byte,
+                    None       => continue
+                }
+
+                assert_eq!(set.span(&a), stop);
+                assert_eq!(set.span(&a[..stop]), stop);
+            }
+        }
+    }
+
+    assert_eq!(ByteSet::ALL.span(&[0xFF; 40]), 40);
+    assert_eq!(ByteSet::EMPTY.span(&[0x00; 40]), 0);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RequestState {
+    Method,
+    Path,
+    Version
+}
+
+struct RequestLine {
+    method:  Vec<u8>,
+    path:    Vec<u8>,
+    state:   RequestState,
+    version: u8
+}
+
+impl RequestLine {
+    fn new() -> RequestLine {
+        RequestLine{ method: Vec::new(), path: Vec::new(), state: RequestState::Method, version: 0 }
+    }
+
+    fn parse(&mut self, bytes: &[u8]) -> ParseResult<usize, u8> {
+        let mut c = ByteStream::new(bytes);
+
+        bs_machine!(c, self.state, {
+            RequestState::Method => {
+                bs_collect_until!(c, c.byte == b' ', {
+                    self.method.extend_from_slice(bs_slice!(c));
+
+                    bs_need_more!();
+                });
+
+                self.method.extend_from_slice(bs_slice_ignore!(c));
+
+                if self.method.is_empty() {
+                    return ParseResult::Error(c.byte);
+                }
+
+                bs_transition!(c, self.state, RequestState::Path);
+            },
+            RequestState::Path => {
+                if let Collect::Eos(_) = bs_collect_set!(c, ByteSet::VISIBLE_7BIT) {
+                    self.path.extend_from_slice(bs_slice!(c));
+
+                    bs_need_more!();
+                }
+
+                self.path.extend_from_slice(bs_slice_ignore!(c));
+                bs_transition!(c, self.state, RequestState::Version);
+            },
+            RequestState::Version => {
+                if bs_is_eos!(c) {
+                    bs_need_more!();
+                }
+
+                bs_next!(c);
+
+                if !is_digit!(c.byte) {
+                    return ParseResult::Error(c.byte);
+                }
+
+                self.version = c.byte - b'0';
+
+                return ParseResult::Done(bs_index!(c));
+            }
+        })
+    }
+}
+
+#[test]
+fn machine() {
+    let mut r = RequestLine::new();
+
+    assert_eq!(r.parse(b"GET /index.html 1"), ParseResult::Done(17));
+    assert_eq!(r.method, b"GET");
+    assert_eq!(r.path, b"/index.html");
+    assert_eq!(r.version, 1);
+    assert_eq!(r.state, RequestState::Version);
+
+    let mut r = RequestLine::new();
+
+    assert!(r.parse(b" /").is_error());
+    assert!(RequestLine::new().parse(b"").is_need_more());
+
+    let mut r = RequestLine::new();
+
+    assert!(r.parse(b"GET /index.html ").is_need_more());
+    assert_eq!(r.state, RequestState::Version);
+    assert_eq!(r.parse(b"1"), ParseResult::Done(1));
+}
+
+#[test]
+fn machine_final_state() {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum State {
+        Digits,
+        Check
+    }
+
+    fn parse(state: &mut State, value: &mut u32, bytes: &[u8]) -> ParseResult<u32, u32> {
+        let mut c = ByteStream::new(bytes);
+
+        bs_machine!(c, *state, {
+            State::Digits => {
+                bs_collect_until!(c, c.byte == b';', {
+                    for byte in bs_slice!(c) {
+                        *value = *value * 10 + (byte - b'0') as u32;
+                    }
+
+                    bs_need_more!();
+                });
+
+                for byte in bs_slice_ignore!(c) {
+                    *value = *value * 10 + (byte - b'0') as u32;
+                }
+
+                bs_transition!(c, *state, State::Check);
+            },
+            State::Check => {
+                if *value > 100 {
+                    return ParseResult::Error(*value);
+                }
+
+                return ParseResult::Done(*value);
+            }
+        })
+    }
+
+    let mut state = State::Digits;
+    let mut value = 0;
+
+    assert_eq!(parse(&mut state, &mut value, b"4"), ParseResult::NeedMore);
+    assert_eq!(parse(&mut state, &mut value, b"2;"), ParseResult::Done(42));
+    assert_eq!(state, State::Check);
+
+    let mut state = State::Digits;
+    let mut value = 0;
+
+    assert_eq!(parse(&mut state, &mut value, b"420;"), ParseResult::Error(420));
+}
+
+#[test]
+fn machine_resume() {
+    let bytes = b"POST /upload 2";
+    let mut r = RequestLine::new();
+
+    for (n, byte) in bytes.iter().enumerate() {
+        let result = r.parse(&[*byte]);
+
+        if n + 1 < bytes.len() {
+            assert_eq!(result, ParseResult::NeedMore);
+        } else {
+            assert!(result.is_done());
+        }
+    }
+
+    assert_eq!(r.method, b"POST");
+    assert_eq!(r.path, b"/upload");
+    assert_eq!(r.version, 2);
+
+    let mut r = RequestLine::new();
+
+    assert_eq!(r.parse(b"PUT /a"), ParseResult::NeedMore);
+    assert_eq!(r.parse(b"b x"), ParseResult::Error(b'x'));
+    assert_eq!(r.path, b"/ab");
+}
+
+#[test]
+fn method_collect_bounded() {
+    let mut c = ByteStream::new(b"404 ");
+
+    assert_eq!(c.collect_bounded::<u16>(10, &DigitPolicy::exact(3)), Collect::Done(404));
+
+    let mut c = ByteStream::new(b"4044");
+
+    assert_eq!(c.collect_bounded::<u16>(10, &DigitPolicy::exact(3)), Collect::Violation(404));
+
+    let mut c = ByteStream::new(b"4 ");
+
+    assert_eq!(c.collect_bounded::<u16>(10, &DigitPolicy::exact(3)), Collect::Violation(4));
+
+    let mut c = ByteStream::new(b"4");
+
+    assert_eq!(c.collect_bounded::<u16>(10, &DigitPolicy::exact(3)), Collect::Eos(4));
+}
+
+#[test]
+fn method_collect_digits() {
+    let mut c = ByteStream::new(b"12345 ");
+
+    assert_eq!(c.collect_digits::<u16>(), Collect::Done(12345));
+    assert_eq!(c.byte(), b' ');
+    assert_eq!(c.index(), 6);
+
+    let mut c = ByteStream::new(b"256");
+
+    assert_eq!(c.collect_digits::<u8>(), Collect::Overflow(25));
+    assert_eq!(c.byte(), b'6');
+
+    let mut c = ByteStream::new(b"255");
+
+    assert_eq!(c.collect_digits::<u8>(), Collect::Eos(255));
+}
+
+#[test]
+fn method_collect_float() {
+    let mut c = ByteStream::new(b"25.5e1 ");
+
+    assert_eq!(c.collect_float::<f64>(&FloatFormat::STRICT), FloatCollect::Done(255.0));
+    assert_eq!(c.byte(), b' ');
+
+    let mut c = ByteStream::new(b"7.");
+
+    assert_eq!(c.collect_float::<f64>(&FloatFormat::STRICT), FloatCollect::Eos(None));
+    assert_eq!(c.byte(), b'.');
+
+    let mut c = ByteStream::new(b"7.");
+
+    assert_eq!(c.collect_float::<f64>(&FloatFormat::RELAXED), FloatCollect::Eos(Some(7.0)));
+
+    let mut c = ByteStream::new(b"x");
+
+    assert_eq!(c.collect_float::<f32>(&FloatFormat::RELAXED), FloatCollect::Invalid);
+    assert_eq!(c.byte(), b'x');
+}
+
+#[test]
+fn method_collect_hex() {
+    let mut c = ByteStream::new(b"FfaZ");
+
+    assert_eq!(c.collect_hex::<u16>(), Collect::Done(0xFFA));
+    assert_eq!(c.byte(), b'Z');
+
+    let mut c = ByteStream::new(b"100");
+
+    assert_eq!(c.collect_hex::<u8>(), Collect::Overflow(0x10));
+
+    let mut c = ByteStream::new(b"ff");
+
+    assert_eq!(c.collect_hex::<u8>(), Collect::Eos(0xFF));
+}
+
+#[test]
+fn method_collect_prefixed() {
+    let mut c = ByteStream::new(b"0xfF,");
+
+    assert_eq!(c.collect_prefixed::<u8>(), Collect::Done(0xFF));
+    assert_eq!(c.byte(), b',');
+
+    let mut c = ByteStream::new(b"0b");
+
+    assert_eq!(c.collect_prefixed::<u8>(), Collect::Eos(0));
+}
+
+#[test]
+fn method_collect_radix() {
+    let mut c = ByteStream::new(b"777 ");
+
+    assert_eq!(c.collect_radix::<u16>(8), Collect::Done(0o777));
+
+    let mut c = ByteStream::new(b"zz");
+
+    assert_eq!(c.collect_radix::<u8>(36), Collect::Overflow(35));
+}
+
+#[test]
+fn method_collect_signed() {
+    let mut c = ByteStream::new(b"-32768,");
+
+    assert_eq!(c.collect_signed::<i16>(), Collect::Done(i16::MIN));
+    assert_eq!(c.byte(), b',');
+
+    let mut c = ByteStream::new(b"+32768");
+
+    assert_eq!(c.collect_signed::<i16>(), Collect::Overflow(3276));
+
+    let mut c = ByteStream::new(b"--1");
+
+    assert_eq!(c.collect_signed::<i16>(), Collect::Done(0));
+}
+
+#[test]
+fn method_collect_length() {
+    let mut c = ByteStream::new(b"stream data");
+
+    assert!(c.collect_length(6));
+    assert_eq!(c.slice(), b"stream");
+    assert!(!c.collect_length(20));
+    assert!(c.is_eos());
+}
+
+#[test]
+fn method_collect_when_until() {
+    let mut c = ByteStream::new(b"stream data");
+
+    assert!(c.collect_when(|byte| is_alpha!(byte)));
+    assert_eq!(c.slice_ignore(), b"stream");
+
+    c.mark();
+
+    assert!(!c.collect_until(|byte| byte == b'!'));
+    assert_eq!(c.slice(), b"data");
+}
+
+#[test]
+fn method_resume() {
+    let mut s = NumberState::new(0u16);
+    let mut c = ByteStream::new(b"65");
+
+    assert_eq!(c.resume_radix(10, &mut s), Collect::Eos(65));
+
+    let mut c = ByteStream::new(b"536");
+
+    assert_eq!(c.resume_radix(10, &mut s), Collect::Overflow(6553));
+
+    let mut s = NumberState::new(0i8);
+    let mut c = ByteStream::new(b"+");
+
+    assert_eq!(c.resume_signed(&mut s), Collect::Eos(0));
+
+    let mut c = ByteStream::new(b"+1");
+
+    assert_eq!(c.resume_signed(&mut s), Collect::Done(0));
+    assert!(s.signed);
+    assert!(!s.negative);
+}
+
+#[test]
+fn method_resume_bounded() {
+    let policy = DigitPolicy{
+        leading_zeros: false,
+        ..DigitPolicy::max(4)
+    };
+
+    let mut s = NumberState::new(0u16);
+    let mut c = ByteStream::new(b"0");
+
+    assert_eq!(c.resume_bounded(16, &policy, &mut s), Collect::Eos(0));
+
+    let mut c = ByteStream::new(b"0");
+
+    assert_eq!(c.resume_bounded(16, &policy, &mut s), Collect::Violation(0));
+
+    let mut s = NumberState::new(0u16);
+    let mut c = ByteStream::new(b"ab");
+
+    assert_eq!(c.resume_bounded(16, &policy, &mut s), Collect::Eos(0xAB));
+
+    let mut c = ByteStream::new(b"cde");
+
+    assert_eq!(c.resume_bounded(16, &policy, &mut s), Collect::Violation(0xABCD));
+}
+
+#[test]
+fn method_resume_prefixed() {
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"0");
+
+    assert_eq!(c.resume_prefixed(&mut s), Collect::Eos(0));
+
+    let mut c = ByteStream::new(b"o");
+
+    assert_eq!(c.resume_prefixed(&mut s), Collect::Eos(0));
+
+    let mut c = ByteStream::new(b"377 ");
+
+    assert_eq!(c.resume_prefixed(&mut s), Collect::Done(0o377));
+
+    let mut s = NumberState::new(0u8);
+    let mut c = ByteStream::new(b"10");
+
+    assert_eq!(c.resume_prefixed(&mut s), Collect::Eos(10));
+
+    let mut c = ByteStream::new(b"x");
+
+    assert_eq!(c.resume_prefixed(&mut s), Collect::Done(10));
+}
+
+#[test]
+fn method_find() {
+    let c = ByteStream::new(b"stream data pattern search");
+
+    assert_eq!(c.find(b' '), Some(6));
+    assert_eq!(c.find_from(7, b' '), Some(11));
+    assert_eq!(c.find_pattern(b"pattern"), Some(12));
+    assert_eq!(c.find_pattern_from(13, b"pattern"), None);
+}
+
+#[test]
+fn method_navigation() {
+    let mut c = ByteStream::new(b"stream data");
+
+    c.next();
+
+    assert_eq!(c.byte(), b's');
+    assert_eq!(c.peek(5), b"tream");
+
+    c.jump(5);
+    c.mark();
+    c.next();
+
+    assert_eq!(c.slice(), b" ");
+    assert_eq!(c.slice_length(), 1);
+    assert_eq!(c.remaining(), b"data");
+    assert!(c.starts_with(b"da"));
+    assert!(c.has_bytes(4));
+    assert_eq!(c.available(), 4);
+
+    c.replay();
+
+    assert_eq!(c.index(), 6);
+
+    c.rewind(6);
+
+    assert_eq!(c.index(), 0);
+
+    c.rewind_to(7);
+
+    assert_eq!(c.remaining(), b"data");
+    assert_eq!(c.byte(), b' ');
+}